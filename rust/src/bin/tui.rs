@@ -0,0 +1,241 @@
+// Terminal frontend for playing and debugging the engine without the browser.
+// Controls are the same as the web version: arrows to move and soft drop, space to hard drop,
+// z and x to rotate, c to hold, plus r to restart and q or escape to quit.
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEventKind, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::style::{self, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use tetris_rs::{Action, Color, Game, PieceType, RotationSystem, Tetrimino};
+
+const WIDTH: usize = 10;
+const HEIGHT: usize = 20;
+
+// the board is drawn two characters per square so the squares come out roughly square
+const BOARD_X: u16 = 14;
+const BOARD_Y: u16 = 1;
+const NEXT_X: u16 = BOARD_X + WIDTH as u16 * 2 + 4;
+
+const FRAME: Duration = Duration::from_millis(16);
+
+// terminals that can't report key releases only send repeated presses while a key is held,
+// soft drop is let go once the presses stop coming for this long
+const SOFT_DROP_HOLD: Duration = Duration::from_millis(100);
+
+fn main() -> io::Result<()> {
+    let mut game = Game::new(WIDTH, HEIGHT).expect("the default board size is valid");
+    let mut out = io::stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+
+    let releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+    if releases {
+        execute!(out, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES))?;
+    }
+
+    let result = run(&mut game, &mut out, releases);
+
+    if releases {
+        execute!(out, PopKeyboardEnhancementFlags)?;
+    }
+    execute!(out, ResetColor, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn run(game: &mut Game, out: &mut Stdout, releases: bool) -> io::Result<()> {
+    let rotation_system = game.rules().rotation_system.build();
+    let mut last_tick = Instant::now();
+    let mut soft_drop_until: Option<Instant> = None;
+
+    loop {
+        // read the keys until it's time for the next frame, each stamped with how far into it it came
+        let deadline = last_tick + FRAME;
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            if !event::poll(timeout)? {
+                break;
+            }
+
+            let key = match event::read()? {
+                Event::Key(key) => key,
+                _ => continue,
+            };
+            let at = last_tick.elapsed().as_micros() as u64;
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('r') if key.kind == KeyEventKind::Press => game.restart_game(),
+                code => {
+                    let action = match action_for(code) {
+                        Some(action) => action,
+                        None => continue,
+                    };
+
+                    match key.kind {
+                        // the engine does its own auto repeat, so repeats are only needed
+                        // on terminals that can't tell when a key is let go
+                        KeyEventKind::Press if releases => game.press(action, at),
+                        KeyEventKind::Release => game.release(action, at),
+                        KeyEventKind::Repeat => {}
+                        KeyEventKind::Press if action == Action::SoftDrop => {
+                            if soft_drop_until.is_none() {
+                                game.press(action, at);
+                            }
+                            soft_drop_until = Some(Instant::now() + SOFT_DROP_HOLD);
+                        }
+                        KeyEventKind::Press => {
+                            game.press(action, at);
+                            game.release(action, at);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(until) = soft_drop_until {
+            if until <= Instant::now() {
+                game.release(Action::SoftDrop, last_tick.elapsed().as_micros() as u64);
+                soft_drop_until = None;
+            }
+        }
+
+        let elapsed = last_tick.elapsed();
+        last_tick = Instant::now();
+        game.update(elapsed.as_micros() as u64);
+
+        draw(game, &*rotation_system, out)?;
+    }
+}
+
+fn action_for(code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Left => Some(Action::MoveLeft),
+        KeyCode::Right => Some(Action::MoveRight),
+        KeyCode::Down => Some(Action::SoftDrop),
+        KeyCode::Char(' ') => Some(Action::HardDrop),
+        KeyCode::Char('z') | KeyCode::Char('Z') => Some(Action::RotateCounterClockwise),
+        KeyCode::Char('x') | KeyCode::Char('X') => Some(Action::RotateClockwise),
+        KeyCode::Char('c') | KeyCode::Char('C') => Some(Action::Hold),
+        _ => None,
+    }
+}
+
+fn ansi(color: Color) -> style::Color {
+    match color {
+        Color::None => style::Color::Reset,
+        Color::Cyan => style::Color::Cyan,
+        Color::Yellow => style::Color::Yellow,
+        Color::Purple => style::Color::Magenta,
+        Color::Green => style::Color::Green,
+        Color::Red => style::Color::Red,
+        Color::Blue => style::Color::Blue,
+        Color::Orange => style::Color::AnsiValue(208),
+        Color::Gray => style::Color::Grey,
+    }
+}
+
+fn draw(game: &Game, rotation_system: &dyn RotationSystem, out: &mut Stdout) -> io::Result<()> {
+    // what to print in every square of the visible board, the ghost goes under the active piece
+    let mut squares: Vec<(&str, Color)> = game.visible_board()
+        .iter()
+        .map(|color| if *color == Color::None { (" .", Color::None) } else { ("██", *color) })
+        .collect();
+
+    if !game.is_lost() {
+        let color = game.active_piece_color();
+        for idx in game.ground_hint_indexes() {
+            squares[*idx as usize] = ("[]", color);
+        }
+        for idx in game.active_piece_indexes() {
+            squares[*idx as usize] = ("██", color);
+        }
+    }
+
+    for y in 0..HEIGHT {
+        queue!(out, MoveTo(BOARD_X - 2, BOARD_Y + y as u16), ResetColor, Print("<!"))?;
+        for (text, color) in &squares[y * WIDTH..(y + 1) * WIDTH] {
+            queue!(out, SetForegroundColor(ansi(*color)), Print(text))?;
+        }
+        queue!(out, ResetColor, Print("!>"))?;
+    }
+    queue!(out, MoveTo(BOARD_X - 2, BOARD_Y + HEIGHT as u16), Print("<!"), Print("=".repeat(WIDTH * 2)), Print("!>"))?;
+
+    queue!(out, MoveTo(1, BOARD_Y), Print("HOLD"))?;
+    draw_piece(out, rotation_system, game.held(), 1, BOARD_Y + 2)?;
+
+    queue!(out, MoveTo(NEXT_X, BOARD_Y), Print("NEXT"))?;
+    for (i, ttype) in game.queue().iter().enumerate() {
+        draw_piece(out, rotation_system, Some(*ttype), NEXT_X, BOARD_Y + 2 + i as u16 * 3)?;
+    }
+
+    queue!(
+        out,
+        MoveTo(1, BOARD_Y + 7), Print("SCORE"),
+        MoveTo(1, BOARD_Y + 8), Print(format!("{:<10}", game.score)),
+        MoveTo(1, BOARD_Y + 10), Print("LEVEL"),
+        MoveTo(1, BOARD_Y + 11), Print(format!("{:<10}", game.level)),
+        MoveTo(1, BOARD_Y + 13), Print("LINES"),
+        MoveTo(1, BOARD_Y + 14), Print(format!("{:<10}", game.lines())),
+        MoveTo(1, BOARD_Y + 16), Print("TIME"),
+        MoveTo(1, BOARD_Y + 17), Print(format!("{:<10}", clock(game.game_time()))),
+    )?;
+
+    // the message is cleared again when the game restarts
+    queue!(out, MoveTo(NEXT_X, BOARD_Y + 12), Clear(ClearType::UntilNewLine),
+           MoveTo(NEXT_X, BOARD_Y + 13), Clear(ClearType::UntilNewLine))?;
+    if game.is_lost() {
+        queue!(
+            out,
+            MoveTo(NEXT_X, BOARD_Y + 12), Print(format!("GAME OVER ({:?})", game.top_out())),
+            MoveTo(NEXT_X, BOARD_Y + 13), Print("r to restart, q to quit"),
+        )?;
+    } else if game.is_finished() {
+        queue!(
+            out,
+            MoveTo(NEXT_X, BOARD_Y + 12), Print(format!("FINISHED in {}", clock(game.game_time()))),
+            MoveTo(NEXT_X, BOARD_Y + 13), Print("r to restart, q to quit"),
+        )?;
+    }
+
+    out.flush()
+}
+
+// game time in microseconds as minutes, seconds and hundredths
+fn clock(micros: u64) -> String {
+    let hundredths = micros / 10_000;
+    format!("{}:{:02}.{:02}", hundredths / 6000, hundredths / 100 % 60, hundredths % 100)
+}
+
+// a piece in its spawn state over two rows, which is as tall as any piece is when it spawns,
+// nothing just blanks the rows out
+fn draw_piece(out: &mut Stdout, rotation_system: &dyn RotationSystem, ttype: Option<PieceType>,
+              x: u16, y: u16) -> io::Result<()> {
+    let (rows, color) = match ttype {
+        Some(ttype) => {
+            let block = rotation_system.block(ttype, rotation_system.spawn_state(ttype));
+            let rows: Vec<u8> = block.iter().copied().filter(|mask| *mask != 0).collect();
+            (rows, ansi(Tetrimino::color_of(ttype)))
+        }
+        None => (Vec::new(), style::Color::Reset),
+    };
+
+    for row in 0..2 {
+        let mask = rows.get(row).copied().unwrap_or(0);
+        queue!(out, MoveTo(x, y + row as u16), SetForegroundColor(color))?;
+        for column in 0..4 {
+            queue!(out, Print(if mask >> column & 1 == 1 { "██" } else { "  " }))?;
+        }
+        queue!(out, ResetColor)?;
+    }
+
+    Ok(())
+}
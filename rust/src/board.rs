@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tetrimino::{Block, Color};
+
+// The playfield as an occupancy bitboard with one u64 per row, bit x set when column x is taken,
+// next to the color of every square for drawing. Collisions and line clears only look at the
+// bits, which is also what bots and simulations want to work with.
+// Rows are numbered from the top, boards can be at most 64 columns wide.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    rows: Vec<u64>,
+    colors: Vec<Color>,
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize) -> Board {
+        Board {
+            width,
+            height,
+            rows: vec![0; height],
+            colors: vec![Color::None; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // a board read back from a snapshot has to be checked before it's used,
+    // including for squares past the right edge of the rows
+    pub(crate) fn has_size(&self, width: usize, height: usize) -> bool {
+        self.width == width && self.height == height
+            && self.rows.len() == height && self.colors.len() == width * height
+            && self.rows.iter().all(|row| row & !self.full_row() == 0)
+    }
+
+    pub fn rows(&self) -> &[u64] {
+        &self.rows
+    }
+
+    // colors of the squares row by row from the top
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    // the bits of a row with every column taken
+    pub fn full_row(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.iter().all(|row| *row == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.iter_mut().for_each(|row| *row = 0);
+        self.colors.iter_mut().for_each(|sq| *sq = Color::None);
+    }
+
+    // whether the square at x, y is taken, anything outside of the board counts as taken
+    pub fn is_occupied(&self, x: isize, y: isize) -> bool {
+        if x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize {
+            return true;
+        }
+
+        self.rows[y as usize] >> x & 1 == 1
+    }
+
+    // fill in or empty a single square
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        if color == Color::None {
+            self.rows[y] &= !(1 << x);
+        } else {
+            self.rows[y] |= 1 << x;
+        }
+        self.colors[y * self.width + x] = color;
+    }
+
+    // whether a block with its 4x4 box at x, y runs into the stack or out of the board
+    pub fn collides(&self, block: &Block, x: isize, y: isize) -> bool {
+        block.iter().enumerate().filter(|(_, row)| **row != 0).any(|(dy, row)| {
+            let board_y = y + dy as isize;
+            if board_y < 0 || board_y >= self.height as isize {
+                return true;
+            }
+
+            match self.columns(*row, x) {
+                Some(columns) => self.rows[board_y as usize] & columns != 0,
+                None => true,
+            }
+        })
+    }
+
+    // whether any square of the block lands on a taken one, squares outside of the board are ignored
+    pub fn overlaps(&self, block: &Block, x: isize, y: isize) -> bool {
+        block.iter().enumerate().any(|(dy, row)| {
+            let board_y = y + dy as isize;
+            if board_y < 0 || board_y >= self.height as isize {
+                return false;
+            }
+
+            self.rows[board_y as usize] & self.clipped(*row, x) != 0
+        })
+    }
+
+    // fill in the squares of a block, the ones outside of the board are left out
+    pub fn place(&mut self, block: &Block, x: isize, y: isize, color: Color) {
+        for (dy, row) in block.iter().enumerate() {
+            let board_y = y + dy as isize;
+            if board_y < 0 || board_y >= self.height as isize {
+                continue;
+            }
+
+            let board_y = board_y as usize;
+            let columns = self.clipped(*row, x);
+            self.rows[board_y] |= columns;
+
+            for board_x in 0..self.width {
+                if columns >> board_x & 1 == 1 {
+                    self.colors[board_y * self.width + board_x] = color;
+                }
+            }
+        }
+    }
+
+    // remove the full rows, move everything above them down and return how many were removed
+    pub fn clear_lines(&mut self) -> usize {
+        let full = self.full_row();
+        let lines = self.rows.iter().filter(|row| **row == full).count();
+        if lines == 0 {
+            return lines;
+        }
+
+        let mut rows = vec![0; lines];
+        let mut colors = vec![Color::None; lines * self.width];
+        for (y, row) in self.rows.iter().enumerate() {
+            if *row != full {
+                rows.push(*row);
+                colors.extend_from_slice(&self.colors[y * self.width..(y + 1) * self.width]);
+            }
+        }
+
+        self.rows = rows;
+        self.colors = colors;
+
+        lines
+    }
+
+    // push every row up by one and fill the bottom row with garbage except for the hole,
+    // returns whether the top row that got pushed off the board had anything in it
+    pub fn push_garbage(&mut self, hole: usize) -> bool {
+        let pushed_off = self.rows.remove(0) != 0;
+        self.rows.push(self.full_row() & !(1 << hole));
+
+        self.colors.drain(..self.width);
+        self.colors.extend((0..self.width).map(|x| if x == hole { Color::None } else { Color::Gray }));
+
+        pushed_off
+    }
+
+    // a row of a block moved over to column x, None if any of its squares ends up off the sides
+    fn columns(&self, row: u8, x: isize) -> Option<u64> {
+        if x < 0 {
+            let cut = (-x).min(8) as u32;
+            if row as u64 & ((1 << cut) - 1) != 0 {
+                return None;
+            }
+
+            return Some(row as u64 >> cut);
+        }
+
+        let columns = (row as u128) << (x as u32).min(64);
+        if columns >> self.width != 0 {
+            return None;
+        }
+
+        Some(columns as u64)
+    }
+
+    // same as columns but squares off the sides are just dropped
+    fn clipped(&self, row: u8, x: isize) -> u64 {
+        let columns = if x < 0 {
+            row as u64 >> (-x).min(8) as u32
+        } else {
+            ((row as u128) << (x as u32).min(64)) as u64
+        };
+
+        columns & self.full_row()
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::mode::Mode;
+use crate::Rules;
+
+// board dimensions a game accepts, every piece needs room to spawn and rotate
+pub const MIN_WIDTH: usize = 4;
+// every row of the board is a u64 bitboard
+pub const MAX_WIDTH: usize = 64;
+pub const MIN_HEIGHT: usize = 4;
+// counts the hidden buffer rows too
+pub const MAX_HEIGHT: usize = 1024;
+
+// the last level of master, games can level up past it but not start there
+pub const MAX_START_LEVEL: usize = 999;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidWidth(usize),
+    // visible rows and the hidden buffer rows above them
+    InvalidHeight(usize, usize),
+    InvalidStartLevel(usize),
+    // a mode that would be over as soon as the first piece locks
+    InvalidGoal(Mode),
+    // a replay, snapshot or fumen that couldn't be read and why
+    InvalidReplay(String),
+    InvalidSnapshot(String),
+    InvalidFumen(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidWidth(width) => write!(
+                f, "board width {} is not between {} and {}", width, MIN_WIDTH, MAX_WIDTH
+            ),
+            Error::InvalidHeight(height, buffer_rows) => write!(
+                f, "board height {} with {} buffer rows needs at least {} visible rows and at most {} in total",
+                height, buffer_rows, MIN_HEIGHT, MAX_HEIGHT
+            ),
+            Error::InvalidStartLevel(level) => write!(
+                f, "start level {} is over {}", level, MAX_START_LEVEL
+            ),
+            Error::InvalidGoal(mode) => write!(f, "{:?} would be over before it starts", mode),
+            Error::InvalidReplay(reason) => write!(f, "invalid replay: {}", reason),
+            Error::InvalidSnapshot(reason) => write!(f, "invalid snapshot: {}", reason),
+            Error::InvalidFumen(reason) => write!(f, "invalid fumen: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// errors reach javascript as thrown `Error`s
+#[cfg(feature = "wasm")]
+impl From<Error> for JsValue {
+    fn from(error: Error) -> JsValue {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
+
+pub fn check_dimensions(width: usize, height: usize, buffer_rows: usize) -> Result<(), Error> {
+    if !(MIN_WIDTH..=MAX_WIDTH).contains(&width) {
+        return Err(Error::InvalidWidth(width));
+    }
+
+    let total = height.saturating_add(buffer_rows);
+    if height < MIN_HEIGHT || total > MAX_HEIGHT {
+        return Err(Error::InvalidHeight(height, buffer_rows));
+    }
+
+    Ok(())
+}
+
+pub fn check_start_level(level: usize) -> Result<(), Error> {
+    if level > MAX_START_LEVEL {
+        return Err(Error::InvalidStartLevel(level));
+    }
+
+    Ok(())
+}
+
+pub fn check_goal(rules: &Rules) -> Result<(), Error> {
+    let nothing_to_do = match rules.mode {
+        Mode::Sprint => rules.line_target == 0,
+        Mode::Dig => rules.garbage_rows == 0,
+        Mode::Marathon | Mode::Ultra | Mode::Survival | Mode::Master => false,
+    };
+    if nothing_to_do {
+        return Err(Error::InvalidGoal(rules.mode));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,786 @@
+use std::time::Duration;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::board::Board;
+use crate::error::Error;
+use crate::randomizer::{Preset, PureRandom};
+use crate::tetrimino::{has_square, Block, Color, PieceType, Tetrimino};
+use crate::mode::Mode;
+use crate::{ActivePiece, Game, Rules, State};
+
+// Reading and writing fumen strings, the v115 format of the fumen diagram editor the community
+// shares positions in. Only what's needed for boards, pieces and comments is supported.
+// https://github.com/knewjade/tetris-fumen
+
+const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const COMMENT_TABLE: &[u8] =
+    b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+const MAX_COMMENT_LENGTH: usize = 4095;
+
+pub const WIDTH: usize = 10;
+// rows above the floor, the field also has a garbage row under the floor that rises into it
+pub const FIELD_TOP: usize = 23;
+const FIELD_BLOCKS: usize = (FIELD_TOP + 1) * WIDTH;
+
+// a page whose field is the same as the previous one's
+const UNCHANGED: usize = 8 * FIELD_BLOCKS + FIELD_BLOCKS - 1;
+
+// The squares of a fumen field, y counts up from the floor and -1 is the garbage row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    cells: Vec<Color>,
+}
+
+impl Field {
+    pub fn new() -> Field {
+        Field { cells: vec![Color::None; FIELD_BLOCKS] }
+    }
+
+    pub fn get(&self, x: usize, y: isize) -> Color {
+        self.cells[Self::index(x, y)]
+    }
+
+    pub fn set(&mut self, x: usize, y: isize, color: Color) {
+        self.cells[Self::index(x, y)] = color;
+    }
+
+    fn index(x: usize, y: isize) -> usize {
+        (FIELD_TOP as isize - 1 - y) as usize * WIDTH + x
+    }
+
+    fn row(&self, y: isize) -> &[Color] {
+        let start = Self::index(0, y);
+        &self.cells[start..start + WIDTH]
+    }
+
+    fn clear_lines(&mut self) {
+        let mut rows: Vec<Vec<Color>> = (0..FIELD_TOP as isize)
+            .map(|y| self.row(y).to_vec())
+            .filter(|row| row.contains(&Color::None))
+            .collect();
+        rows.resize(FIELD_TOP, vec![Color::None; WIDTH]);
+
+        for (y, row) in rows.iter().enumerate() {
+            let start = Self::index(0, y as isize);
+            self.cells[start..start + WIDTH].copy_from_slice(row);
+        }
+    }
+
+    // push the field up and bring the garbage row in at the bottom
+    fn rise(&mut self) {
+        for y in (0..FIELD_TOP as isize).rev() {
+            let below = self.row(y - 1).to_vec();
+            let start = Self::index(0, y);
+            self.cells[start..start + WIDTH].copy_from_slice(&below);
+        }
+
+        for x in 0..WIDTH {
+            self.set(x, -1, Color::None);
+        }
+    }
+
+    fn mirror(&mut self) {
+        for y in 0..FIELD_TOP as isize {
+            let start = Self::index(0, y);
+            self.cells[start..start + WIDTH].reverse();
+        }
+    }
+}
+
+impl Default for Field {
+    fn default() -> Self {
+        Field::new()
+    }
+}
+
+// A piece on a page, x and y are the square it rotates around with y counting up from the floor.
+// Rotations are numbered clockwise from the spawn state like the rest of the engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Operation {
+    pub ttype: PieceType,
+    pub rotation: usize,
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Operation {
+    // the squares of the piece, in field coordinates
+    pub fn cells(&self) -> [(isize, isize); 4] {
+        Self::shape(self.ttype, self.rotation).map(|(x, y)| (self.x + x, self.y + y))
+    }
+
+    // squares of a piece around its center in the given rotation state
+    pub fn shape(ttype: PieceType, rotation: usize) -> [(isize, isize); 4] {
+        let spawn = match ttype {
+            PieceType::I => [(0, 0), (-1, 0), (1, 0), (2, 0)],
+            PieceType::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+            PieceType::T => [(0, 0), (-1, 0), (1, 0), (0, 1)],
+            PieceType::S => [(0, 0), (-1, 0), (0, 1), (1, 1)],
+            PieceType::Z => [(0, 0), (1, 0), (0, 1), (-1, 1)],
+            PieceType::J => [(0, 0), (-1, 0), (1, 0), (-1, 1)],
+            PieceType::L => [(0, 0), (-1, 0), (1, 0), (1, 1)],
+        };
+
+        spawn.map(|(x, y)| match rotation % 4 {
+            0 => (x, y),
+            1 => (y, -x),
+            2 => (-x, -y),
+            _ => (-y, x),
+        })
+    }
+
+    // fumen keeps O, I, S and Z positions off by one in some rotations
+    fn offset(&self) -> (isize, isize) {
+        match (self.ttype, self.rotation) {
+            (PieceType::O, 3) => (-1, 1),
+            (PieceType::O, 2) => (-1, 0),
+            (PieceType::O, 0) => (0, 1),
+            (PieceType::I, 2) => (-1, 0),
+            (PieceType::I, 3) => (0, 1),
+            (PieceType::S, 0) => (0, 1),
+            (PieceType::S, 1) => (1, 0),
+            (PieceType::Z, 0) => (0, 1),
+            (PieceType::Z, 3) => (-1, 0),
+            _ => (0, 0),
+        }
+    }
+
+    fn fits(&self) -> bool {
+        self.cells().iter().all(|(x, y)| (0..WIDTH as isize).contains(x) && (0..FIELD_TOP as isize).contains(y))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page {
+    pub field: Field,
+    pub operation: Option<Operation>,
+    // the piece locks into the field for the next page and full lines are cleared,
+    // then the garbage row rises and the field is mirrored if those are set
+    pub lock: bool,
+    pub rise: bool,
+    pub mirror: bool,
+    pub comment: String,
+}
+
+impl Page {
+    pub fn new(field: Field) -> Page {
+        Page {
+            field,
+            operation: None,
+            lock: true,
+            rise: false,
+            mirror: false,
+            comment: String::new(),
+        }
+    }
+
+    // the field the page after this one starts from
+    pub fn next_field(&self) -> Field {
+        let mut field = self.field.clone();
+        if !self.lock {
+            return field;
+        }
+
+        if let Some(operation) = self.operation {
+            for (x, y) in operation.cells() {
+                if (0..WIDTH as isize).contains(&x) && (-1..FIELD_TOP as isize).contains(&y) {
+                    field.set(x as usize, y, Tetrimino::color_of(operation.ttype));
+                }
+            }
+        }
+
+        field.clear_lines();
+        if self.rise {
+            field.rise();
+        }
+        if self.mirror {
+            field.mirror();
+        }
+
+        field
+    }
+}
+
+pub fn decode(data: &str) -> Result<Vec<Page>, Error> {
+    let data = data.trim();
+    let body = ["v115@", "m115@", "d115@"].iter()
+        .find_map(|prefix| data.strip_prefix(prefix))
+        .ok_or_else(|| invalid("only v115 fumen is supported"))?;
+
+    // '?' only breaks long strings up
+    let digits = body.bytes()
+        .filter(|c| *c != b'?')
+        .map(|c| TABLE.iter().position(|t| *t == c).ok_or_else(|| invalid("unexpected character")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut reader = Reader { digits, position: 0 };
+
+    let mut pages = Vec::new();
+    let mut field = Field::new();
+    let mut repeat = 0;
+    let mut comment = String::new();
+
+    while !reader.is_empty() {
+        if repeat > 0 {
+            repeat -= 1;
+        } else if !read_field(&mut reader, &mut field)? {
+            repeat = reader.poll(1)?;
+        }
+
+        let mut action = reader.poll(3)?;
+        let ttype = action % 8;
+        action /= 8;
+        let rotation = action % 4;
+        action /= 4;
+        let position = action % FIELD_BLOCKS;
+        action /= FIELD_BLOCKS;
+        let rise = action % 2 == 1;
+        let mirror = action / 2 % 2 == 1;
+        let has_comment = action / 8 % 2 == 1;
+        let lock = action / 16 % 2 == 0;
+
+        let operation = piece_of(ttype).map(|ttype| {
+            let mut operation = Operation {
+                ttype,
+                rotation: fumen_rotation(rotation),
+                x: (position % WIDTH) as isize,
+                y: FIELD_TOP as isize - (position / WIDTH) as isize - 1,
+            };
+            let (dx, dy) = operation.offset();
+            operation.x -= dx;
+            operation.y -= dy;
+            operation
+        });
+
+        if has_comment {
+            comment = read_comment(&mut reader)?;
+        }
+
+        let page = Page { field: field.clone(), operation, lock, rise, mirror, comment: comment.clone() };
+        field = page.next_field();
+        pages.push(page);
+    }
+
+    Ok(pages)
+}
+
+pub fn encode(pages: &[Page]) -> String {
+    let mut digits = Vec::new();
+    let mut previous = Field::new();
+    let mut comment = String::new();
+    // where the count of unchanged pages in a row is, so following ones can add to it
+    let mut repeat: Option<usize> = None;
+
+    for (i, page) in pages.iter().enumerate() {
+        let changed = write_field(&mut digits, &previous, &page.field);
+        match repeat {
+            _ if changed => repeat = None,
+            Some(at) if digits[at] < TABLE.len() - 1 => {
+                digits[at] += 1;
+            }
+            _ => {
+                push(&mut digits, UNCHANGED, 2);
+                digits.push(0);
+                repeat = Some(digits.len() - 1);
+            }
+        }
+
+        let has_comment = page.comment != comment || (i == 0 && !page.comment.is_empty());
+        let operation = page.operation.filter(|operation| operation.fits());
+        let (ttype, rotation, position) = match operation {
+            Some(operation) => {
+                let (dx, dy) = operation.offset();
+                let x = operation.x + dx;
+                let y = operation.y + dy;
+                let position = (FIELD_TOP as isize - y - 1) as usize * WIDTH + x as usize;
+                (number_of_piece(operation.ttype), fumen_rotation(operation.rotation), position)
+            }
+            None => (0, 0, 0),
+        };
+
+        // the guideline colors flag is only ever set on the first page
+        let mut action = !page.lock as usize;
+        action = action * 2 + has_comment as usize;
+        action = action * 2 + (i == 0) as usize;
+        action = action * 2 + page.mirror as usize;
+        action = action * 2 + page.rise as usize;
+        action = action * FIELD_BLOCKS + position;
+        action = action * 4 + rotation;
+        action = action * 8 + ttype;
+        push(&mut digits, action, 3);
+
+        if has_comment {
+            comment = page.comment.clone();
+            write_comment(&mut digits, &comment);
+        }
+
+        previous = page.next_field();
+    }
+
+    let data: String = digits.iter().map(|d| TABLE[*d] as char).collect();
+
+    // same line breaks as the editor puts in
+    let mut out = String::from("v115@");
+    for (i, c) in data.chars().enumerate() {
+        if i >= 42 && (i - 42) % 47 == 0 {
+            out.push('?');
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidFumen(reason.to_string())
+}
+
+struct Reader {
+    digits: Vec<usize>,
+    position: usize,
+}
+
+impl Reader {
+    fn is_empty(&self) -> bool {
+        self.position >= self.digits.len()
+    }
+
+    // numbers are written as little endian base 64 digits
+    fn poll(&mut self, count: usize) -> Result<usize, Error> {
+        let mut value = 0;
+        for i in 0..count {
+            let digit = self.digits.get(self.position).ok_or_else(|| invalid("data ends early"))?;
+            value += digit * TABLE.len().pow(i as u32);
+            self.position += 1;
+        }
+
+        Ok(value)
+    }
+}
+
+fn push(digits: &mut Vec<usize>, mut value: usize, count: usize) {
+    for _ in 0..count {
+        digits.push(value % TABLE.len());
+        value /= TABLE.len();
+    }
+}
+
+// fields are written as runs of squares that changed by the same amount from the previous field,
+// returns whether anything changed
+fn read_field(reader: &mut Reader, field: &mut Field) -> Result<bool, Error> {
+    let mut changed = true;
+    let mut index = 0;
+
+    while index < FIELD_BLOCKS {
+        let run = reader.poll(2)?;
+        if run == UNCHANGED {
+            changed = false;
+        }
+
+        let diff = run / FIELD_BLOCKS;
+        for _ in 0..run % FIELD_BLOCKS + 1 {
+            if index >= FIELD_BLOCKS {
+                return Err(invalid("field runs past the end"));
+            }
+
+            let value = (number_of_color(field.cells[index]) + diff)
+                .checked_sub(8)
+                .and_then(color_of_number)
+                .ok_or_else(|| invalid("unknown block"))?;
+            field.cells[index] = value;
+            index += 1;
+        }
+    }
+
+    Ok(changed)
+}
+
+fn write_field(digits: &mut Vec<usize>, previous: &Field, field: &Field) -> bool {
+    let diffs: Vec<usize> = previous.cells.iter().zip(&field.cells)
+        .map(|(before, after)| number_of_color(*after) + 8 - number_of_color(*before))
+        .collect();
+
+    if diffs.iter().all(|diff| *diff == 8) {
+        return false;
+    }
+
+    let mut start = 0;
+    while start < diffs.len() {
+        let length = diffs[start..].iter().take_while(|diff| **diff == diffs[start]).count();
+        push(digits, diffs[start] * FIELD_BLOCKS + length - 1, 2);
+        start += length;
+    }
+
+    true
+}
+
+// comments are escaped like javascript's escape() and packed four characters at a time
+fn read_comment(reader: &mut Reader) -> Result<String, Error> {
+    let length = reader.poll(2)?;
+    let mut escaped = String::new();
+
+    while escaped.len() < length {
+        let mut value = reader.poll(5)?;
+        for _ in 0..4 {
+            let c = COMMENT_TABLE.get(value % (COMMENT_TABLE.len() + 1))
+                .ok_or_else(|| invalid("unknown comment character"))?;
+            escaped.push(*c as char);
+            value /= COMMENT_TABLE.len() + 1;
+        }
+    }
+    escaped.truncate(length);
+
+    Ok(unescape(&escaped))
+}
+
+fn write_comment(digits: &mut Vec<usize>, comment: &str) {
+    let mut escaped = escape(comment);
+    escaped.truncate(MAX_COMMENT_LENGTH);
+    push(digits, escaped.len(), 2);
+
+    for chunk in escaped.as_bytes().chunks(4) {
+        let value = chunk.iter().rev().fold(0, |value, c| {
+            let c = COMMENT_TABLE.iter().position(|t| t == c).unwrap_or(0);
+            value * (COMMENT_TABLE.len() + 1) + c
+        });
+        push(digits, value, 5);
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for unit in text.encode_utf16() {
+        match char::from_u32(unit as u32) {
+            Some(c) if c.is_ascii_alphanumeric() || "@*_+-./".contains(c) => escaped.push(c),
+            _ if unit < 256 => escaped.push_str(&format!("%{:02X}", unit)),
+            _ => escaped.push_str(&format!("%u{:04X}", unit)),
+        }
+    }
+
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut units = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = |from: usize, len: usize| {
+            text.get(from..from + len).and_then(|hex| u16::from_str_radix(hex, 16).ok())
+        };
+
+        let (unit, next) = match bytes[i] {
+            b'%' if bytes.get(i + 1) == Some(&b'u') => match hex(i + 2, 4) {
+                Some(unit) => (unit, i + 6),
+                None => (b'%' as u16, i + 1),
+            },
+            b'%' => match hex(i + 1, 2) {
+                Some(unit) => (unit, i + 3),
+                None => (b'%' as u16, i + 1),
+            },
+            c => (c as u16, i + 1),
+        };
+        units.push(unit);
+        i = next;
+    }
+
+    String::from_utf16_lossy(&units)
+}
+
+// fumen numbers its rotations from the reverse state, 0 reverse, 1 right, 2 spawn and 3 left,
+// which maps onto the engine's spawn, right, reverse and left the same way both ways
+fn fumen_rotation(rotation: usize) -> usize {
+    [2, 1, 0, 3][rotation % 4]
+}
+
+// pieces and blocks are numbered 1 I, 2 L, 3 O, 4 Z, 5 T, 6 J, 7 S and 8 for garbage
+fn piece_of(number: usize) -> Option<PieceType> {
+    match number {
+        1 => Some(PieceType::I),
+        2 => Some(PieceType::L),
+        3 => Some(PieceType::O),
+        4 => Some(PieceType::Z),
+        5 => Some(PieceType::T),
+        6 => Some(PieceType::J),
+        7 => Some(PieceType::S),
+        _ => None,
+    }
+}
+
+fn number_of_piece(ttype: PieceType) -> usize {
+    match ttype {
+        PieceType::I => 1,
+        PieceType::L => 2,
+        PieceType::O => 3,
+        PieceType::Z => 4,
+        PieceType::T => 5,
+        PieceType::J => 6,
+        PieceType::S => 7,
+    }
+}
+
+fn color_of_number(number: usize) -> Option<Color> {
+    match number {
+        0 => Some(Color::None),
+        1 => Some(Color::Cyan),
+        2 => Some(Color::Orange),
+        3 => Some(Color::Yellow),
+        4 => Some(Color::Red),
+        5 => Some(Color::Purple),
+        6 => Some(Color::Blue),
+        7 => Some(Color::Green),
+        8 => Some(Color::Gray),
+        _ => None,
+    }
+}
+
+fn number_of_color(color: Color) -> usize {
+    match color {
+        Color::None => 0,
+        Color::Cyan => 1,
+        Color::Orange => 2,
+        Color::Yellow => 3,
+        Color::Red => 4,
+        Color::Purple => 5,
+        Color::Blue => 6,
+        Color::Green => 7,
+        Color::Gray => 8,
+    }
+}
+
+// the queue a quiz comment sets up, "#Q=[hold](current)next"
+pub struct Quiz {
+    pub hold: Option<PieceType>,
+    pub current: Option<PieceType>,
+    pub next: Vec<PieceType>,
+}
+
+impl Quiz {
+    pub fn parse(comment: &str) -> Option<Quiz> {
+        let rest = comment.strip_prefix("#Q=[")?;
+        let (hold, rest) = rest.split_once("](")?;
+        let (current, next) = rest.split_once(')')?;
+        let next = next.split(';').next().unwrap_or("");
+
+        let piece = |s: &str| s.chars().next().and_then(piece_of_letter);
+        Some(Quiz {
+            hold: piece(hold),
+            current: piece(current),
+            next: next.trim().chars().map(piece_of_letter).collect::<Option<Vec<_>>>()?,
+        })
+    }
+
+    pub fn to_comment(&self) -> String {
+        let letter = |ttype: Option<PieceType>| ttype.map(letter_of).map(String::from).unwrap_or_default();
+        let next: String = self.next.iter().map(|t| letter_of(*t)).collect();
+
+        format!("#Q=[{}]({}){}", letter(self.hold), letter(self.current), next)
+    }
+}
+
+fn piece_of_letter(letter: char) -> Option<PieceType> {
+    match letter.to_ascii_uppercase() {
+        'I' => Some(PieceType::I),
+        'O' => Some(PieceType::O),
+        'T' => Some(PieceType::T),
+        'S' => Some(PieceType::S),
+        'Z' => Some(PieceType::Z),
+        'J' => Some(PieceType::J),
+        'L' => Some(PieceType::L),
+        _ => None,
+    }
+}
+
+fn letter_of(ttype: PieceType) -> char {
+    match ttype {
+        PieceType::I => 'I',
+        PieceType::O => 'O',
+        PieceType::T => 'T',
+        PieceType::S => 'S',
+        PieceType::Z => 'Z',
+        PieceType::J => 'J',
+        PieceType::L => 'L',
+    }
+}
+
+// games set up from a fumen are the usual 20 rows, its rows above that go into the buffer rows
+const VISIBLE_HEIGHT: usize = 20;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    // A game set up from a page of a fumen. Its field becomes the bottom of the board and the piece
+    // on the page the active piece, right where the page has it. A quiz comment, "#Q=[hold](current)next",
+    // sets up the held piece and the queue, the randomizer deals once the queue runs out.
+    // The set up position isn't part of the seed, so these games can't be replayed.
+    pub fn from_fumen(data: &str, page: usize, rules: Rules, seed: u64) -> Result<Game, Error> {
+        let pages = decode(data)?;
+        let page = pages.get(page).ok_or_else(|| invalid("no such page"))?;
+
+        // the page is the whole position, no garbage from the mode goes under it,
+        // the rows with gray squares at the bottom of it are what dig has to clear
+        let mut game = Game::with_rules(WIDTH, VISIBLE_HEIGHT, rules, seed)?;
+        game.frames = None;
+        game.replayable = false;
+        game.board.clear();
+        game.garbage_lines = 0;
+        for y in 0..FIELD_TOP as isize {
+            for x in 0..WIDTH {
+                let color = page.field.get(x, y);
+                if color == Color::None {
+                    continue;
+                }
+
+                let row = game.height as isize - 1 - y;
+                if row < 0 {
+                    return Err(invalid("field is taller than the board"));
+                }
+                game.board.set(x, row as usize, color);
+            }
+        }
+        game.garbage_lines = (0..FIELD_TOP as isize)
+            .take_while(|y| (0..WIDTH).any(|x| page.field.get(x, *y) == Color::Gray))
+            .count();
+        if rules.mode == Mode::Dig && game.garbage_lines == 0 {
+            return Err(invalid("dig needs rows of garbage at the bottom of the page"));
+        }
+
+        let quiz = Quiz::parse(&page.comment).unwrap_or(Quiz { hold: None, current: None, next: Vec::new() });
+
+        // the queue is topped up with what the randomizer already dealt, pieces past the
+        // three shown come out of the randomizer before anything else
+        let mut next = quiz.next;
+        let preset = next.split_off(next.len().min(3));
+        next.extend_from_slice(&game.next_pieces);
+        next.truncate(3);
+        game.next_pieces = next;
+        if !preset.is_empty() {
+            let generator = std::mem::replace(&mut game.generator, Box::new(PureRandom));
+            game.generator = Box::new(Preset::new(preset, generator));
+        }
+
+        game.held_type = quiz.hold;
+        match (page.operation, quiz.current) {
+            (Some(operation), _) => game.enter_operation(operation)?,
+            (None, Some(ttype)) => game.enter_piece(ttype),
+            // the dealt piece spawns again so it's checked against the new field
+            (None, None) => game.enter_piece(game.active_piece.piece.ttype),
+        }
+
+        game.update_active_piece_coords();
+        game.update_ground_hint_coords();
+        game.clear_history();
+
+        Ok(game)
+    }
+
+    // Every piece locked so far as a page that locks it into the field it landed on, whether or not
+    // undo is on, then a page with the game as it is now. Every page has a quiz comment with the
+    // hold and the queue at the time.
+    // Only the bottom 23 rows fit in a fumen field and only ten wide games can be exported.
+    pub fn to_fumen(&self) -> Result<String, Error> {
+        if self.width != WIDTH {
+            return Err(invalid("fumen fields are ten columns wide"));
+        }
+
+        let mut pages = self.history.pages().to_vec();
+        let mut page = Page::new(self.field_of(&self.board));
+        if self.state == State::Playing {
+            page.operation = self.operation_of(self.active_piece);
+        }
+        page.lock = false;
+        page.comment = quiz_comment(self.held_type, self.active_piece, &self.next_pieces);
+        pages.push(page);
+
+        Ok(encode(&pages))
+    }
+}
+
+impl Game {
+    // keep the active piece as a page right before it locks, for exporting the game later
+    pub(crate) fn record_page(&mut self) {
+        if self.width != WIDTH {
+            return;
+        }
+
+        let mut page = Page::new(self.field_of(&self.board));
+        page.operation = self.operation_of(self.active_piece);
+        page.comment = quiz_comment(self.held_type, self.active_piece, &self.next_pieces);
+        self.history.record_page(page);
+    }
+
+    // make the piece on a page the active one, in whichever rotation state has the same shape
+    fn enter_operation(&mut self, operation: Operation) -> Result<(), Error> {
+        let cells = sorted(operation.cells().map(|(x, y)| (x, self.height as isize - 1 - y)).to_vec());
+
+        let piece = (0..4).map(|turn| (operation.rotation + turn) % 4).find_map(|state| {
+            let squares = squares(self.rotation_system.block(operation.ttype, state));
+            let (x, y) = (cells[0].0 - squares[0].0, cells[0].1 - squares[0].1);
+            let same = squares.iter().zip(&cells).all(|(sq, cell)| (sq.0 + x, sq.1 + y) == *cell);
+
+            same.then_some(ActivePiece { piece: Tetrimino::from(operation.ttype, state), x, y })
+        }).ok_or_else(|| invalid("the rotation system has no state for the page's piece"))?;
+
+        let block = self.rotation_system.block(operation.ttype, piece.piece.state);
+        if self.board.collides(block, piece.x, piece.y) {
+            return Err(invalid("the piece on the page doesn't fit in the field"));
+        }
+
+        self.active_piece = piece;
+        self.last_rotation = None;
+        self.lock_elapsed = Duration::from_micros(0);
+        self.lock_resets = 0;
+        self.lowest_row = piece.y;
+
+        Ok(())
+    }
+
+    fn field_of(&self, board: &Board) -> Field {
+        let mut field = Field::new();
+        for y in 0..FIELD_TOP.min(self.height) {
+            let row = self.height - 1 - y;
+            for x in 0..WIDTH {
+                field.set(x, y as isize, board.colors()[row * WIDTH + x]);
+            }
+        }
+
+        field
+    }
+
+    // the page piece with the same squares, the one in the matching rotation state if there's a choice
+    fn operation_of(&self, piece: ActivePiece) -> Option<Operation> {
+        let ttype = piece.piece.ttype;
+        let block = self.rotation_system.block(ttype, piece.piece.state);
+        let cells = sorted(squares(block).iter()
+            .map(|(x, y)| (piece.x + x, self.height as isize - 1 - (piece.y + y)))
+            .collect());
+
+        (0..4).map(|turn| (piece.piece.state + turn) % 4).find_map(|rotation| {
+            let shape = sorted(Operation::shape(ttype, rotation).to_vec());
+            let (x, y) = (cells[0].0 - shape[0].0, cells[0].1 - shape[0].1);
+            let same = shape.iter().zip(&cells).all(|(sq, cell)| (sq.0 + x, sq.1 + y) == *cell);
+
+            same.then_some(Operation { ttype, rotation, x, y })
+        })
+    }
+}
+
+fn squares(block: &Block) -> Vec<(isize, isize)> {
+    let squares = (0..4)
+        .flat_map(|y| (0..4).map(move |x| (x, y)))
+        .filter(|(x, y)| has_square(block, *x, *y))
+        .map(|(x, y)| (x as isize, y as isize))
+        .collect();
+
+    sorted(squares)
+}
+
+fn sorted(mut squares: Vec<(isize, isize)>) -> Vec<(isize, isize)> {
+    squares.sort_by_key(|(x, y)| (*y, *x));
+    squares
+}
+
+fn quiz_comment(hold: Option<PieceType>, active: ActivePiece, next: &[PieceType]) -> String {
+    Quiz { hold, current: Some(active.piece.ttype), next: next.to_vec() }.to_comment()
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Grades of TGM's master mode, from 9 up to S9 by score, with GM only for a game that
+// reaches level 999 having made every checkpoint on the way in time.
+// https://tetris.wiki/Tetris_The_Grand_Master#Grading_system
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    Nine,
+    Eight,
+    Seven,
+    Six,
+    Five,
+    Four,
+    Three,
+    Two,
+    One,
+    S1,
+    S2,
+    S3,
+    S4,
+    S5,
+    S6,
+    S7,
+    S8,
+    S9,
+    Gm,
+}
+
+// score needed for every grade from 9 to S9
+const GRADE_SCORES: [(Grade, usize); 18] = [
+    (Grade::Nine, 0), (Grade::Eight, 400), (Grade::Seven, 800), (Grade::Six, 1400), (Grade::Five, 2000),
+    (Grade::Four, 3500), (Grade::Three, 5500), (Grade::Two, 8000), (Grade::One, 12000),
+    (Grade::S1, 16000), (Grade::S2, 22000), (Grade::S3, 30000), (Grade::S4, 40000), (Grade::S5, 52000),
+    (Grade::S6, 66000), (Grade::S7, 82000), (Grade::S8, 100000), (Grade::S9, 120000),
+];
+
+// levels a game going for GM has to reach with the score in the time,
+// grade 1 at level 300, S4 at level 500 and a bit more than S9 at the end
+pub const GM_CHECKPOINTS: [(usize, usize, Duration); 3] = [
+    (300, 12000, Duration::from_secs(4 * 60 + 15)),
+    (500, 40000, Duration::from_secs(7 * 60 + 30)),
+    (999, 126000, Duration::from_secs(13 * 60 + 30)),
+];
+
+impl Grade {
+    pub fn for_score(score: usize) -> Grade {
+        GRADE_SCORES.iter().rev()
+            .find(|(_, needed)| score >= *needed)
+            .map_or(Grade::Nine, |(grade, _)| *grade)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Grade::Nine => "9",
+            Grade::Eight => "8",
+            Grade::Seven => "7",
+            Grade::Six => "6",
+            Grade::Five => "5",
+            Grade::Four => "4",
+            Grade::Three => "3",
+            Grade::Two => "2",
+            Grade::One => "1",
+            Grade::S1 => "S1",
+            Grade::S2 => "S2",
+            Grade::S3 => "S3",
+            Grade::S4 => "S4",
+            Grade::S5 => "S5",
+            Grade::S6 => "S6",
+            Grade::S7 => "S7",
+            Grade::S8 => "S8",
+            Grade::S9 => "S9",
+            Grade::Gm => "GM",
+        }
+    }
+}
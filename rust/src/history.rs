@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::board::Board;
+use crate::fumen::Page;
+use crate::randomizer::RandomizerState;
+use crate::rng::Rng;
+use crate::tetrimino::PieceType;
+use crate::topout::TopOut;
+use crate::{mode, ActivePiece, Game, LineClear, State};
+
+// The game right after a piece locked and the next one came in. The rng and the randomizer
+// are saved along with the queue, so going back to a placement deals the same pieces again
+// and undoing can't be used to get a different queue.
+#[derive(Clone)]
+struct Placement {
+    board: Board,
+    rng: Rng,
+    generator: RandomizerState,
+    next_pieces: Vec<PieceType>,
+    active_piece: ActivePiece,
+    held_type: Option<PieceType>,
+    can_hold: bool,
+    last_clear: LineClear,
+    combo: Option<usize>,
+    back_to_back: bool,
+    state: State,
+    top_out: TopOut,
+    lines: usize,
+    goal_remaining: usize,
+    garbage_lines: usize,
+    garbage_hole: Option<usize>,
+    score: usize,
+    level: usize,
+    // how many pieces had locked by then
+    pieces: usize,
+}
+
+// placements from the oldest to the newest, position is the one the game is at right now
+// and the ones after it can be redone until the next piece locks
+// Every piece that locked is kept as a fumen page too, with the field it locked into. Those are
+// kept whether undo is on or not so the whole game can be exported, pieces is how many of them
+// lead up to where the game is now.
+pub(crate) struct History {
+    placements: VecDeque<Placement>,
+    position: usize,
+    pages: Vec<Page>,
+    pieces: usize,
+}
+
+impl History {
+    pub(crate) fn new() -> History {
+        History {
+            placements: VecDeque::new(),
+            position: 0,
+            pages: Vec::new(),
+            pieces: 0,
+        }
+    }
+
+    fn push(&mut self, placement: Placement, limit: usize) {
+        self.placements.truncate(self.position + 1);
+        self.placements.push_back(placement);
+
+        // the first placement is where undoing stops, so it's kept on top of the limit
+        while self.placements.len() > limit + 1 {
+            self.placements.pop_front();
+        }
+        self.position = self.placements.len() - 1;
+    }
+
+    // a piece about to lock, anything undone before it can't be redone anymore
+    pub(crate) fn record_page(&mut self, page: Page) {
+        self.pages.truncate(self.pieces);
+        self.pages.push(page);
+        self.pieces += 1;
+    }
+
+    // the pieces locked so far, oldest first
+    pub(crate) fn pages(&self) -> &[Page] {
+        &self.pages[..self.pieces]
+    }
+}
+
+impl Game {
+    // remember the game as it is now, called every time a piece locks
+    pub(crate) fn save_placement(&mut self) {
+        if self.rules.undo_limit == 0 {
+            return;
+        }
+
+        let placement = Placement {
+            board: self.board.clone(),
+            rng: self.rng,
+            generator: self.generator.save(),
+            next_pieces: self.next_pieces.clone(),
+            active_piece: self.active_piece,
+            held_type: self.held_type,
+            can_hold: self.can_hold,
+            last_clear: self.last_clear,
+            combo: self.combo,
+            back_to_back: self.back_to_back,
+            state: self.state,
+            top_out: self.top_out,
+            lines: self.lines,
+            goal_remaining: self.goal_remaining,
+            garbage_lines: self.garbage_lines,
+            garbage_hole: self.garbage_hole,
+            score: self.score,
+            level: self.level,
+            pieces: self.history.pieces,
+        };
+        self.history.push(placement, self.rules.undo_limit);
+    }
+
+    pub(crate) fn clear_history(&mut self) {
+        self.history = History::new();
+        self.save_placement();
+    }
+
+    // go back to the placement before the current one, false if there isn't one
+    pub(crate) fn undo_placement(&mut self) -> bool {
+        if self.history.position == 0 || self.history.placements.is_empty() {
+            return false;
+        }
+
+        self.history.position -= 1;
+        self.restore_placement();
+        true
+    }
+
+    pub(crate) fn redo_placement(&mut self) -> bool {
+        if self.history.position + 1 >= self.history.placements.len() {
+            return false;
+        }
+
+        self.history.position += 1;
+        self.restore_placement();
+        true
+    }
+
+    fn restore_placement(&mut self) {
+        let placement = self.history.placements[self.history.position].clone();
+
+        self.board = placement.board;
+        self.rng = placement.rng;
+        self.generator.load(&placement.generator);
+        self.next_pieces = placement.next_pieces;
+        self.active_piece = placement.active_piece;
+        self.held_type = placement.held_type;
+        self.can_hold = placement.can_hold;
+        self.last_clear = placement.last_clear;
+        self.combo = placement.combo;
+        self.back_to_back = placement.back_to_back;
+        self.state = placement.state;
+        self.top_out = placement.top_out;
+        self.lines = placement.lines;
+        self.goal_remaining = placement.goal_remaining;
+        self.garbage_lines = placement.garbage_lines;
+        self.garbage_hole = placement.garbage_hole;
+        self.score = placement.score;
+        self.level = placement.level;
+        self.history.pieces = placement.pieces;
+        self.fall_rate = self.rules.gravity.fall_rate(self.level);
+
+        // the clock keeps running, only splits and sections that were taken back go
+        self.splits.truncate(self.lines / mode::SPLIT_LINES);
+        self.sections.truncate(self.level / mode::SECTION_LEVELS);
+
+        // the piece starts over as if it just came in
+        self.last_rotation = None;
+        self.entry_delay = None;
+        self.elapsed = Duration::from_micros(0);
+        self.lock_elapsed = Duration::from_micros(0);
+        self.lock_resets = 0;
+        self.lowest_row = self.active_piece.y;
+        self.inputs.clear();
+
+        self.update_active_piece_coords();
+        self.update_ground_hint_coords();
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// the keys a player can press, each of them is sent to the game as a press and a release
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Hold,
+}
+
+// Keeps track of the held left and right keys and charges the delayed auto shift.
+// The piece shifts once when a key is pressed, then again after the DAS and after that
+// every ARR for as long as the key is held down.
+// https://tetris.wiki/DAS
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AutoShift {
+    left: bool,
+    right: bool,
+
+    // -1 or 1 for the most recently pressed of the held keys, 0 when none is held
+    direction: isize,
+
+    // time the current direction has been held for
+    held: Duration,
+}
+
+impl AutoShift {
+    pub fn new() -> AutoShift {
+        AutoShift {
+            left: false,
+            right: false,
+            direction: 0,
+            held: Duration::from_micros(0),
+        }
+    }
+
+    pub fn direction(&self) -> isize {
+        self.direction
+    }
+
+    pub fn press(&mut self, direction: isize) {
+        if direction < 0 {
+            self.left = true;
+        } else {
+            self.right = true;
+        }
+
+        self.direction = direction;
+        self.held = Duration::from_micros(0);
+    }
+
+    // letting go of one key while the other one is still held starts charging the other way
+    pub fn release(&mut self, direction: isize) {
+        if direction < 0 {
+            self.left = false;
+        } else {
+            self.right = false;
+        }
+
+        if self.direction != direction {
+            return;
+        }
+
+        self.direction = match (self.left, self.right) {
+            (true, _) => -1,
+            (_, true) => 1,
+            _ => 0,
+        };
+        self.held = Duration::from_micros(0);
+    }
+
+    // charge for the elapsed time and return how many columns the piece should shift,
+    // None means an ARR of 0 and the piece goes all the way to the wall
+    pub fn charge(&mut self, elapsed: Duration, das: Duration, arr: Duration) -> Option<usize> {
+        if self.direction == 0 {
+            return Some(0);
+        }
+
+        let before = self.held;
+        self.held += elapsed;
+
+        if self.held < das {
+            return Some(0);
+        }
+
+        if arr == Duration::from_micros(0) {
+            return None;
+        }
+
+        let repeats = |held: Duration| match held.checked_sub(das) {
+            Some(charged) => (charged.as_nanos() / arr.as_nanos()) as usize + 1,
+            None => 0,
+        };
+
+        Some(repeats(self.held) - repeats(before))
+    }
+}
+
+impl Default for AutoShift {
+    fn default() -> Self {
+        AutoShift::new()
+    }
+}
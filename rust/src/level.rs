@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::LineClear;
+use crate::tspin::TSpin;
+
+// NES runs at 60.0988 frames a second, the gravity table below is counted in these frames
+const NES_FRAME_NANOS: u64 = 16_639_267;
+
+// TGM and most other games run at 60 frames a second
+const FRAME_NANOS: u64 = 1_000_000_000 / 60;
+
+// 20 rows a frame, the whole field, pieces this fast land the moment they appear
+pub const TWENTY_G: Duration = Duration::from_nanos(FRAME_NANOS / 20);
+
+// TGM's gravity in 1/256ths of a row a frame from each level on, 5120 is 20G
+// https://tetris.wiki/Tetris_The_Grand_Master#Gravity
+const TGM_GRAVITY: [(usize, u64); 30] = [
+    (0, 4), (30, 6), (35, 8), (40, 10), (50, 12), (60, 16), (70, 32), (80, 48), (90, 64), (100, 80),
+    (120, 96), (140, 112), (160, 128), (170, 144), (200, 4), (220, 32), (230, 64), (233, 96), (236, 128), (239, 160),
+    (243, 192), (247, 224), (251, 256), (300, 512), (330, 768), (360, 1024), (400, 1280), (420, 1024), (450, 768), (500, 5120),
+];
+
+// frames per row for each NES level, every level from 29 onwards drops a row every frame
+// https://tetris.wiki/Tetris_(NES,_Nintendo)#Gravity
+const NES_GRAVITY: [u64; 30] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6,
+    5, 5, 5, 4, 4, 4, 3, 3, 3, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 1,
+];
+
+// how fast pieces fall at each level
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GravityCurve {
+    // (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds per row, capped at level 20
+    // https://tetris.wiki/Marathon#Gravity
+    Guideline,
+    // the NES frame table, level 1 here is level 0 on the NES
+    Nes,
+    // TGM's 20G, pieces fall 20 rows a frame and land the moment they appear
+    Twenty,
+    // TGM's master mode curve for levels 0 to 999, speeding up to 20G at level 500
+    Master,
+}
+
+impl GravityCurve {
+    // time it takes a piece to fall one row at the given level
+    pub fn fall_rate(self, level: usize) -> Duration {
+        match self {
+            GravityCurve::Guideline => {
+                let level = level.clamp(1, 20) as f64;
+                let seconds = (0.8 - (level - 1.0) * 0.007).powf(level - 1.0);
+                Duration::from_secs_f64(seconds)
+            }
+            GravityCurve::Nes => {
+                let frames = NES_GRAVITY[level.saturating_sub(1).min(NES_GRAVITY.len() - 1)];
+                Duration::from_nanos(frames * NES_FRAME_NANOS)
+            }
+            GravityCurve::Twenty => TWENTY_G,
+            GravityCurve::Master => {
+                let (_, gravity) = TGM_GRAVITY.iter().rev().find(|(from, _)| level >= *from).unwrap();
+                Duration::from_nanos(FRAME_NANOS * 256 / gravity)
+            }
+        }
+    }
+}
+
+// how many lines it takes to get to the next level
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LevelGoal {
+    // every 10 lines
+    Fixed,
+    // 5 times the current level, with bigger clears and spins counting as more than their lines
+    // https://tetris.wiki/Marathon#Variable_goal
+    Variable,
+}
+
+impl LevelGoal {
+    // lines needed to finish the given level
+    pub fn goal(self, level: usize) -> usize {
+        match self {
+            LevelGoal::Fixed => 10,
+            LevelGoal::Variable => 5 * level,
+        }
+    }
+
+    // lines a clear counts towards the goal
+    pub fn awarded(self, clear: &LineClear) -> usize {
+        match self {
+            LevelGoal::Fixed => clear.lines,
+            LevelGoal::Variable => {
+                let awarded = match (clear.tspin, clear.lines) {
+                    (TSpin::None, 0) => 0,
+                    (TSpin::None, 1) => 1,
+                    (TSpin::None, 2) => 3,
+                    (TSpin::None, 3) => 5,
+                    (TSpin::None, _) => 8,
+                    (TSpin::Mini, 0) => 1,
+                    (TSpin::Mini, _) => 2,
+                    (TSpin::Full, 0) => 4,
+                    (TSpin::Full, 1) => 8,
+                    (TSpin::Full, 2) => 12,
+                    (TSpin::Full, _) => 16,
+                };
+
+                if clear.back_to_back {
+                    awarded * 3 / 2
+                } else {
+                    awarded
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,48 @@
-use std::collections::HashSet;
 use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+use history::History;
+use input::AutoShift;
+use snapshot::Snapshot;
+
+pub use board::Board;
+pub use error::Error;
+pub use grade::Grade;
+pub use input::Action;
+pub use rng::Rng;
+pub use level::*;
+pub use lock::LockPolicy;
+pub use mode::{GarbageHoles, Mode};
+pub use randomizer::*;
+pub use replay::{Frame, RecordedInput, Replay, ReplayPlayer};
+pub use rotation::*;
+pub use scoring::*;
 pub use tetrimino::*;
+pub use topout::TopOut;
+pub use tspin::TSpin;
 
 mod utils;
+pub mod board;
+pub mod error;
+pub mod fumen;
+pub mod grade;
+mod history;
+pub mod input;
+pub mod level;
+pub mod lock;
+pub mod mode;
+pub mod randomizer;
+pub mod replay;
+pub mod rng;
+pub mod rotation;
+pub mod scoring;
+mod snapshot;
 pub mod tetrimino;
+pub mod topout;
+pub mod tspin;
 
 // When the `wee_alloc` feature is enabled, this uses `wee_alloc` as the global
 // allocator.
@@ -17,6 +53,7 @@ pub mod tetrimino;
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 // This is like the `main` function, except for JavaScript.
+#[cfg(feature = "wasm")]
 #[wasm_bindgen(start)]
 pub fn main_js() -> Result<(), JsValue> {
     // This provides better error messages in debug mode.
@@ -27,10 +64,14 @@ pub fn main_js() -> Result<(), JsValue> {
     Ok(())
 }
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 enum State {
     Playing,
     Lost,
+    // the goal of the mode was reached
+    Finished,
+    // the time limit ran out
+    TimeUp,
 }
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
@@ -39,31 +80,188 @@ enum Rotation {
     CounterClockwise,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 enum Event {
-    MoveLeft,
-    MoveRight,
-    MoveDown,
-    Rotate(Rotation),
-    Drop,
-    Hold,
+    Press(Action),
+    Release(Action),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Input {
+    at: Duration,
+    event: Event,
+}
+
+// what the last locked piece did, lines can be 0 for a placement that cleared nothing
+// combo counts the clears in a row before this one, back_to_back is set when this and
+// the previous clear were both difficult ones, tetrises or spins that cleared lines
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineClear {
+    pub tspin: TSpin,
+    pub lines: usize,
+    pub combo: usize,
+    pub back_to_back: bool,
+    pub perfect_clear: bool,
+}
+
+impl LineClear {
+    fn empty() -> LineClear {
+        LineClear {
+            tspin: TSpin::None,
+            lines: 0,
+            combo: 0,
+            back_to_back: false,
+            perfect_clear: false,
+        }
+    }
+
+    fn is_difficult(&self) -> bool {
+        self.lines == 4 || (self.lines > 0 && self.tspin != TSpin::None)
+    }
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize)]
 struct ActivePiece {
     piece: Tetrimino,
     x: isize,
     y: isize,
 }
 
-#[wasm_bindgen]
+// rules of the game that can be chosen when a game is created,
+// rules saved before a rule was added read it as its default
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub rotation_system: RotationSystemKind,
+    pub scoring_rule: ScoringRuleKind,
+    pub randomizer: RandomizerKind,
+    pub gravity: GravityCurve,
+    pub level_goal: LevelGoal,
+    pub start_level: usize,
+    pub lock_policy: LockPolicy,
+    pub lock_delay_ms: u32,
+    // how many times a piece can restart its lock timer under move reset
+    pub max_lock_resets: usize,
+
+    // delayed auto shift and auto repeat rate for holding left or right,
+    // an ARR of 0 moves the piece straight to the wall once DAS is charged
+    pub das_ms: u32,
+    pub arr_ms: u32,
+
+    // how many times faster than gravity the piece falls while soft drop is held,
+    // 0 drops it straight to the ground
+    pub soft_drop_factor: u32,
+
+    // hidden rows above the visible field, pieces spawn in there and the stack can grow into them
+    pub buffer_rows: usize,
+
+    // which top out conditions end the game, with block out turned off a new piece is pushed up
+    // until it fits and the game only ends when there's no room left for it
+    pub block_out: bool,
+    pub lock_out: bool,
+    pub garbage_out: bool,
+
+    // how many placements can be taken back with undo, 0 turns undo off like in a normal game
+    pub undo_limit: usize,
+
+    pub mode: Mode,
+    // lines to clear in sprint and how long ultra lasts
+    pub line_target: usize,
+    pub time_limit_ms: u32,
+
+    // rows of garbage dig starts with, and how long survival waits before the first row rises
+    pub garbage_rows: usize,
+    pub garbage_holes: GarbageHoles,
+    pub garbage_interval_ms: u32,
+
+    // ARE, the wait between a piece locking and the next one coming in,
+    // and how much longer the wait is when the piece cleared lines
+    pub are_ms: u32,
+    pub line_clear_delay_ms: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Rules {
+    pub fn new() -> Rules {
+        Rules::default()
+    }
+
+    // TGM's master mode, with its timings at 60 frames a second
+    pub fn master() -> Rules {
+        Rules {
+            rotation_system: RotationSystemKind::Ars,
+            scoring_rule: ScoringRuleKind::Tgm,
+            randomizer: RandomizerKind::Tgm,
+            gravity: GravityCurve::Master,
+            start_level: 0,
+            lock_policy: LockPolicy::StepReset,
+            lock_delay_ms: 500,
+            das_ms: 267,
+            arr_ms: 16,
+            are_ms: 500,
+            line_clear_delay_ms: 683,
+            mode: Mode::Master,
+            ..Rules::default()
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            rotation_system: RotationSystemKind::Srs,
+            scoring_rule: ScoringRuleKind::Guideline,
+            randomizer: RandomizerKind::SevenBag,
+            gravity: GravityCurve::Guideline,
+            level_goal: LevelGoal::Fixed,
+            start_level: 1,
+            lock_policy: LockPolicy::MoveReset,
+            lock_delay_ms: 500,
+            max_lock_resets: 15,
+            das_ms: 167,
+            arr_ms: 33,
+            soft_drop_factor: 20,
+            buffer_rows: 20,
+            block_out: true,
+            lock_out: true,
+            garbage_out: true,
+            undo_limit: 0,
+            mode: Mode::Marathon,
+            line_target: 40,
+            time_limit_ms: 120_000,
+            garbage_rows: 10,
+            garbage_holes: GarbageHoles::Random,
+            garbage_interval_ms: 4000,
+            are_ms: 0,
+            line_clear_delay_ms: 0,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct Game {
+    // height counts every row of the board, the first buffer_rows of them are hidden
+    // above the visible field, which is all the frontend gets to see
     width: usize,
     height: usize,
-    board: Vec<Color>,
+    buffer_rows: usize,
+    board: Board,
+
+    rules: Rules,
+
+    // shapes, spawn positions and wall kicks of the pieces
+    rotation_system: Box<dyn RotationSystem>,
 
-    // generator to generate next pieces randomly,
+    scoring_rule: Box<dyn ScoringRule>,
+
+    // generator to generate next pieces randomly from the game's own random numbers,
+    // a game always deals the same pieces for the same seed
     // next_pieces will always contain three pieces according to the ui
-    generator: SevenGenerator,
+    seed: u64,
+    rng: Rng,
+    generator: Box<dyn Randomizer>,
     next_pieces: Vec<PieceType>,
 
     // current playing piece and it's x, y coordinate
@@ -76,66 +274,141 @@ pub struct Game {
     held_type: Option<PieceType>,
     can_hold: bool,
 
+    // index of the wall kick used if the last successful action on the active piece was a rotation,
+    // any movement after the rotation clears it since spins only count when the piece is twisted in
+    last_rotation: Option<usize>,
+
+    // result of the last piece that locked
+    last_clear: LineClear,
+
+    // number of line clears in a row, None when the last piece didn't clear anything
+    combo: Option<usize>,
+
+    // whether the last line clear was a difficult one, spins without lines keep it going
+    back_to_back: bool,
+
     state: State,
 
+    // how the game ended once it's lost
+    top_out: TopOut,
+
     // elapsed time since the last update
     elapsed: Duration,
 
-    // fall rate defines how fast a piece fall
-    // if time delta greater than this is elapsed, I move one piece down by one unit
+    // fall rate defines how fast a piece fall, it's picked from the gravity curve on every level up
+    // for every multiple of this elapsed, I move the piece down by one unit
     fall_rate: Duration,
 
+    // time left before the active piece comes in after the last one locked, it's on the board
+    // already but can't be moved or seen until then
+    entry_delay: Option<Duration>,
+
+    // time the active piece has been resting on the ground, it locks once this reaches the lock delay
+    lock_elapsed: Duration,
+    lock_delay: Duration,
+
+    // times the active piece has restarted its lock timer, only counted under move reset
+    lock_resets: usize,
+
+    // lowest row the active piece has reached, falling below it always restarts the lock timer
+    lowest_row: isize,
+
+    // lines cleared in total and lines still needed to reach the next level
+    lines: usize,
+    goal_remaining: usize,
+
+    // time the game has been played for, only updates count so time spent paused doesn't,
+    // and the game time of every SPLIT_LINES lines
+    game_time: Duration,
+    splits: Vec<Duration>,
+
+    // rows of garbage left at the bottom of the board, garbage never has anything under it
+    // so these are always the bottom rows
+    garbage_lines: usize,
+
+    // hole of the last row of garbage, and in survival the time since the last row rose
+    // and how long until the next one does
+    garbage_hole: Option<usize>,
+    garbage_timer: Duration,
+    garbage_interval: Duration,
+
+    // game time at the end of every section of 100 levels in master, and whether
+    // the game has made every checkpoint for GM so far, a game started past level 0
+    // skips some of them so it can't get GM at all
+    sections: Vec<Duration>,
+    gm_qualified: bool,
+
     // vector to hold pairs of x,y coordinates in the form of index
     // for current active piece's individual squares
     // have to do this way because there is no other good way to pass a vector
     // without incurring performance cost for serializing into js
-    active_piece_indexes: Vec<u8>,
+    active_piece_indexes: Vec<u32>,
 
     // similar vector of x,y coordinates to draw the hint on the ground
-    ground_hint_indexes: Vec<u8>,
+    ground_hint_indexes: Vec<u32>,
+
+    // inputs for the next update in the order they happened, each stamped with
+    // how far into the frame it came in so fast inputs are never merged or reordered
+    inputs: Vec<Input>,
+
+    // every update the game got with its inputs, so it can be saved as a replay,
+    // None for a game picked up from a save that left them out or that had something
+    // happen to it a replay can't play back
+    frames: Option<Vec<Frame>>,
+
+    // whether a restart can be replayed, false when pieces come from a randomizer
+    // the rules don't know about
+    replayable: bool,
+
+    // placements that undo and redo move through, only kept when the rules allow undo,
+    // and every piece locked so far for exporting to fumen
+    history: History,
+
+    // held left and right keys and how far their auto shift is charged
+    auto_shift: AutoShift,
 
-    // Set to hold all the user events for this update
-    // Set because I only want to process one event of each
-    events: HashSet<Event>,
+    // whether the soft drop key is held down
+    soft_drop: bool,
 
     pub score: usize,
+    pub level: usize,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Game {
-    pub fn new(width: usize, height: usize) -> Game {
-        let mut generator = SevenGenerator::new();
-        let mut next_pieces = Vec::new();
-        next_pieces.push(generator.next().unwrap());
-        next_pieces.push(generator.next().unwrap());
-        next_pieces.push(generator.next().unwrap());
+    // width and height are the visible field, they're checked against the limits in `error`
+    pub fn new(width: usize, height: usize) -> Result<Game, Error> {
+        Self::with_rules(width, height, Rules::default(), utils::random_seed())
+    }
 
-        let active_piece = Self::initialize_tetrimino(generator.next().unwrap());
+    pub fn new_with_seed(width: usize, height: usize, seed: u64) -> Result<Game, Error> {
+        Self::with_rules(width, height, Rules::default(), seed)
+    }
 
-        let mut game = Game {
-            width,
-            height,
-            board: vec![Color::None; width * height],
-            generator,
-            next_pieces,
-            active_piece,
-            held_type: None,
-            can_hold: true,
-            state: State::Playing,
-            elapsed: Duration::from_micros(0),
-            fall_rate: Duration::from_millis(500), // TODO: this should update
-            active_piece_indexes: Vec::new(),
-            ground_hint_indexes: Vec::new(),
-            events: HashSet::new(),
-            score: 0,
-        };
-        game.update_active_piece_coords();
+    pub fn with_rules(width: usize, height: usize, rules: Rules, seed: u64) -> Result<Game, Error> {
+        let mut game = Self::with_randomizer(width, height, rules, seed, rules.randomizer.build())?;
 
-        game
+        // the randomizer comes from the rules, so a replay deals the same pieces
+        game.replayable = true;
+        game.frames = Some(Vec::new());
+        Ok(game)
     }
 
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    // only the visible part of the board, the hidden rows above it are left out
     pub fn board(&self) -> *const Color {
-        self.board.as_ptr()
+        self.visible_board().as_ptr()
+    }
+
+    pub fn buffer_rows(&self) -> usize {
+        self.buffer_rows
     }
 
     pub fn next_pieces(&self) -> *const PieceType {
@@ -146,7 +419,7 @@ impl Game {
         self.active_piece_indexes.len()
     }
 
-    pub fn active_piece_coords(&self) -> *const u8 {
+    pub fn active_piece_coords(&self) -> *const u32 {
         self.active_piece_indexes.as_ptr()
     }
 
@@ -154,203 +427,695 @@ impl Game {
         self.ground_hint_indexes.len()
     }
 
-    pub fn ground_hint_coords(&self) -> *const u8 {
+    pub fn ground_hint_coords(&self) -> *const u32 {
         self.ground_hint_indexes.as_ptr()
     }
 
+    pub fn active_piece_type(&self) -> PieceType {
+        self.active_piece.piece.ttype
+    }
+
     pub fn active_piece_color(&self) -> Color {
         self.active_piece.piece.color()
     }
 
+    // advance the game by the elapsed microseconds, applying the inputs queued for this frame
+    // in order at the point of the frame they happened
     pub fn update(&mut self, elapsed: u64) {
-        if self.state == State::Lost {
+        // keys pressed after the game is over have nothing to do, and the replay ends with the game
+        if self.state != State::Playing {
+            self.inputs.clear();
             return;
         }
 
+        self.record_frame(elapsed);
+
         let elapsed = Duration::from_micros(elapsed);
-        self.elapsed += elapsed;
+        let mut now = Duration::from_micros(0);
 
-        // delta movement across x and y axis, must process these movements separately
-        // limit these movement to 1 on each tick because I want smooth movement animation
-        let mut delta_x = 0;
-        let mut delta_y = 0;
-
-        let mut rotation = None;
-
-        if self.elapsed >= self.fall_rate {
-            delta_y = 1;
-            self.elapsed -= self.fall_rate;
-        }
-
-        for event in &self.events {
-            match event {
-                // These movement change delta value independently
-                Event::MoveLeft => delta_x -= 1,
-                Event::MoveRight => delta_x += 1,
-
-                // only update if it's 0
-                Event::MoveDown => if delta_y == 0 {
-                    delta_y = 1
-                },
-                Event::Drop => delta_y = self.height,
-
-                Event::Rotate(rot) => rotation = Some(*rot),
-                Event::Hold => {
-                    if self.can_hold {
-                        let current_type = match self.held_type {
-                            Some(held) => held,
-                            None => {
-                                let t = self.next_pieces[0];
-                                self.next_pieces[0] = self.next_pieces[1];
-                                self.next_pieces[1] = self.next_pieces[2];
-                                self.next_pieces[2] = self.generator.next().unwrap();
-
-                                t
-                            }
-                        };
-
-                        self.held_type = Some(self.active_piece.piece.ttype);
-                        self.can_hold = false;
-                        self.active_piece = Self::initialize_tetrimino(current_type);
-                    }
-                }
+        for input in std::mem::take(&mut self.inputs) {
+            // inputs stamped after the end of the frame are applied at the end of it
+            let at = input.at.min(elapsed);
+            self.advance(at - now);
+            now = at;
+
+            if self.state != State::Playing {
+                break;
             }
+            self.apply(input.event);
         }
-        self.events.clear();
-
-        if let Some(rot) = rotation {
-            let new_piece = match rot {
-                Rotation::Clockwise => self.active_piece.piece.rotate_clockwise(),
-                Rotation::CounterClockwise => self.active_piece.piece.rotate_counter_clockwise()
-            };
-            let block = new_piece.block();
 
-            if self.can_fit_block(block, self.active_piece.x, self.active_piece.y) {
-                self.active_piece.piece = new_piece;
-            }
+        if self.state == State::Playing {
+            self.advance(elapsed - now);
         }
 
-        let block = self.active_piece.piece.block();
+        self.update_active_piece_coords();
+        self.update_ground_hint_coords();
+    }
+
+    // `at` is when the key was pressed in microseconds into the frame the next update covers
+    pub fn press(&mut self, action: Action, at: u64) {
+        self.queue_input(Event::Press(action), at);
+    }
+
+    pub fn release(&mut self, action: Action, at: u64) {
+        self.queue_input(Event::Release(action), at);
+    }
 
-        if delta_x != 0 && self.can_fit_block(block, self.active_piece.x + delta_x, self.active_piece.y) {
-            self.active_piece.x += delta_x;
+    pub fn get_held(&self) -> u8 {
+        match self.held_type {
+            Some(t) => t as u8,
+            None => 0
         }
+    }
 
-        if delta_y > 1 {
-            // the player dropped the active piece, move the active piece until it hit the ground
-            for _ in 0..delta_y {
-                if self.can_fit_block(block, self.active_piece.x, self.active_piece.y + 1) {
-                    self.active_piece.y += 1;
-                }
-            }
+    // microseconds left before the active piece locks, the timer only runs while the piece is on
+    // the ground and starts over when it falls lower than it's been, so stepping off a ledge onto
+    // the same row doesn't buy any time
+    pub fn lock_remaining(&self) -> u64 {
+        self.lock_delay.saturating_sub(self.lock_elapsed).as_micros() as u64
+    }
 
-            self.try_fuse_active_piece();
-            self.erase_lines();
-            self.check_game_over();
-        } else if delta_y == 1 {
-            // fusing and dropping are considered two separate fall events
-            // to allow player to move the pieces into the gaps in the middle of the board
-            // if the block can drop one more line it must mean the piece wasn't touching the ground
-            // else the piece was touching the ground on the previous tick, and should be merged
-            if self.can_fit_block(block, self.active_piece.x, self.active_piece.y + 1) {
-                self.active_piece.y += 1;
-            } else {
-                self.try_fuse_active_piece();
-                self.erase_lines();
-                self.check_game_over();
-            }
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn goal_remaining(&self) -> usize {
+        self.goal_remaining
+    }
+
+    pub fn last_clear(&self) -> LineClear {
+        self.last_clear
+    }
+
+    pub fn is_lost(&self) -> bool {
+        self.state == State::Lost
+    }
+
+    // the goal of the mode was reached, sprint's lines cleared
+    pub fn is_finished(&self) -> bool {
+        self.state == State::Finished
+    }
+
+    // microseconds of play so far
+    pub fn game_time(&self) -> u64 {
+        self.game_time.as_micros() as u64
+    }
+
+    // game time in microseconds at every SPLIT_LINES lines cleared
+    pub fn splits(&self) -> Vec<u64> {
+        self.splits.iter().map(|split| split.as_micros() as u64).collect()
+    }
+
+    // lines left to clear in sprint
+    pub fn lines_remaining(&self) -> usize {
+        self.rules.line_target.saturating_sub(self.lines)
+    }
+
+    // rows of garbage still to clear
+    pub fn garbage_remaining(&self) -> usize {
+        self.garbage_lines
+    }
+
+    // microseconds until survival pushes up the next row of garbage, 0 in other modes
+    pub fn next_garbage(&self) -> u64 {
+        match self.rules.mode {
+            Mode::Survival => self.garbage_interval.saturating_sub(self.garbage_timer).as_micros() as u64,
+            _ => 0,
         }
+    }
 
-        self.update_active_piece_coords();
-        self.update_ground_hint_coords();
+    // grade by score, GM for making every checkpoint and reaching level 999 in master
+    pub fn grade(&self) -> Grade {
+        if self.rules.mode == Mode::Master && self.level >= mode::MASTER_LEVEL && self.gm_qualified {
+            Grade::Gm
+        } else {
+            Grade::for_score(self.score)
+        }
     }
 
-    pub fn move_left(&mut self) {
-        self.events.insert(Event::MoveLeft);
+    // microseconds every finished section of 100 levels in master took
+    pub fn section_times(&self) -> Vec<u64> {
+        let mut start = Duration::from_micros(0);
+        self.sections.iter().map(|end| {
+            let time = *end - start;
+            start = *end;
+            time.as_micros() as u64
+        }).collect()
     }
 
-    pub fn move_right(&mut self) {
-        self.events.insert(Event::MoveRight);
+    // microseconds before the next piece comes in, 0 while it's in play
+    pub fn entry_remaining(&self) -> u64 {
+        self.entry_delay.map_or(0, |wait| wait.as_micros() as u64)
     }
 
-    pub fn move_down(&mut self) {
-        self.events.insert(Event::MoveDown);
+    // ultra's time limit ran out
+    pub fn is_time_up(&self) -> bool {
+        self.state == State::TimeUp
     }
 
-    pub fn rotate_clockwise(&mut self) {
-        self.events.insert(Event::Rotate(Rotation::Clockwise));
+    // microseconds left in ultra, 0 in modes without a time limit
+    pub fn time_remaining(&self) -> u64 {
+        self.time_left().map_or(0, |left| left.as_micros() as u64)
     }
 
-    pub fn rotate_counter_clockwise(&mut self) {
-        self.events.insert(Event::Rotate(Rotation::CounterClockwise));
+    pub fn top_out(&self) -> TopOut {
+        self.top_out
     }
 
-    pub fn drop(&mut self) {
-        self.events.insert(Event::Drop);
+    // the game so far as a replay file
+    pub fn replay_json(&self) -> Option<String> {
+        self.replay().map(|replay| replay.to_json())
     }
 
-    pub fn hold(&mut self) {
-        self.events.insert(Event::Hold);
+    // a game played to the end of the replay
+    pub fn from_replay_json(json: &str) -> Result<Game, Error> {
+        Replay::from_json(json)?.play()
     }
 
-    pub fn get_held(&self) -> u8 {
-        match self.held_type {
-            Some(t) => t as u8,
-            None => 0
+    // everything the game is in the middle of, to pick it up again later with load_state,
+    // except for the placements to undo, the loaded game can't undo past where it was saved
+    pub fn save_state(&self) -> Vec<u8> {
+        self.snapshot(true).to_bytes()
+    }
+
+    pub fn load_state(bytes: &[u8]) -> Result<Game, Error> {
+        Game::from_snapshot(Snapshot::from_bytes(bytes)?)
+    }
+
+    // same as save_state but readable, for tests and debugging
+    pub fn save_state_json(&self) -> String {
+        self.snapshot(true).to_json()
+    }
+
+    // a save that stays the same size however long the game goes on, for the browser to keep
+    // between visits, the game loaded from it carries on without a replay
+    pub fn save_state_json_without_replay(&self) -> String {
+        self.snapshot(false).to_json()
+    }
+
+    pub fn load_state_json(json: &str) -> Result<Game, Error> {
+        Game::from_snapshot(Snapshot::from_json(json)?)
+    }
+
+    // take back the last placement, the next pieces stay the same, false if there's nothing to undo,
+    // a replay can't take placements back so the game doesn't have one anymore
+    pub fn undo(&mut self) -> bool {
+        if !self.undo_placement() {
+            return false;
         }
+
+        self.frames = None;
+        true
     }
 
-    pub fn is_lost(&self) -> bool {
-        self.state == State::Lost
+    // put back a placement that was undone, until a different piece locks
+    pub fn redo(&mut self) -> bool {
+        self.redo_placement()
     }
 
     pub fn restart_game(&mut self) {
+        self.restart_with_seed(utils::random_seed());
+    }
+
+    // restarting with the seed the game was created with deals the same pieces again
+    pub fn restart_with_seed(&mut self, seed: u64) {
         self.state = State::Playing;
+        self.top_out = TopOut::None;
         self.score = 0;
-        for sq in self.board.iter_mut() {
-            *sq = Color::None;
-        }
+        self.level = self.rules.start_level;
+        self.lines = 0;
+        self.goal_remaining = self.rules.level_goal.goal(self.level);
+        self.fall_rate = self.rules.gravity.fall_rate(self.level);
+        self.game_time = Duration::from_micros(0);
+        self.splits.clear();
+        self.sections.clear();
+        self.gm_qualified = self.rules.start_level == 0;
+        self.entry_delay = None;
+        self.board.clear();
         self.active_piece_indexes.clear();
         self.ground_hint_indexes.clear();
-        self.events.clear();
-
-        self.generator = SevenGenerator::new();
-        self.next_pieces = Vec::new();
-        self.next_pieces.push(self.generator.next().unwrap());
-        self.next_pieces.push(self.generator.next().unwrap());
-        self.next_pieces.push(self.generator.next().unwrap());
-        self.active_piece = Self::initialize_tetrimino(self.generator.next().unwrap());
+        self.inputs.clear();
+        self.frames = self.replayable.then(Vec::new);
+        self.auto_shift = AutoShift::new();
+        self.soft_drop = false;
+
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+        self.generator.reset();
+        self.next_pieces = vec![
+            self.generator.next(&mut self.rng),
+            self.generator.next(&mut self.rng),
+            self.generator.next(&mut self.rng),
+        ];
+        let ttype = self.generator.next(&mut self.rng);
+        self.start_mode();
+        self.enter_piece(ttype);
         self.held_type = None;
         self.can_hold = true;
+        self.last_clear = LineClear::empty();
+        self.combo = None;
+        self.back_to_back = false;
         self.elapsed = Duration::from_micros(0);
         self.update_active_piece_coords();
+        self.clear_history();
     }
 }
 
 impl Game {
+    // create a game dealing pieces from any randomizer, not just the ones in RandomizerKind,
+    // it has no replay since one couldn't deal the same pieces
+    pub fn with_randomizer(width: usize, height: usize, rules: Rules, seed: u64,
+                           mut generator: Box<dyn Randomizer>) -> Result<Game, Error> {
+        error::check_dimensions(width, height, rules.buffer_rows)?;
+        error::check_start_level(rules.start_level)?;
+        error::check_goal(&rules)?;
+
+        let rotation_system = rules.rotation_system.build();
+        let scoring_rule = rules.scoring_rule.build();
+        let mut rng = Rng::new(seed);
+        let next_pieces = vec![
+            generator.next(&mut rng),
+            generator.next(&mut rng),
+            generator.next(&mut rng),
+        ];
+
+        let first = generator.next(&mut rng);
+        let active_piece = Self::initialize_tetrimino(&*rotation_system, width, 0, first);
+
+        let buffer_rows = rules.buffer_rows;
+        let height = height + buffer_rows;
+
+        let mut game = Game {
+            width,
+            height,
+            buffer_rows,
+            board: Board::new(width, height),
+            rules,
+            rotation_system,
+            scoring_rule,
+            seed,
+            rng,
+            generator,
+            next_pieces,
+            active_piece,
+            held_type: None,
+            can_hold: true,
+            last_rotation: None,
+            last_clear: LineClear::empty(),
+            combo: None,
+            back_to_back: false,
+            state: State::Playing,
+            top_out: TopOut::None,
+            elapsed: Duration::from_micros(0),
+            fall_rate: rules.gravity.fall_rate(rules.start_level),
+            entry_delay: None,
+            lock_elapsed: Duration::from_micros(0),
+            lock_delay: Duration::from_millis(rules.lock_delay_ms as u64),
+            lock_resets: 0,
+            lowest_row: active_piece.y,
+            lines: 0,
+            goal_remaining: rules.level_goal.goal(rules.start_level),
+            game_time: Duration::from_micros(0),
+            splits: Vec::new(),
+            garbage_lines: 0,
+            garbage_hole: None,
+            garbage_timer: Duration::from_micros(0),
+            garbage_interval: Duration::from_micros(0),
+            sections: Vec::new(),
+            gm_qualified: rules.start_level == 0,
+            active_piece_indexes: Vec::new(),
+            ground_hint_indexes: Vec::new(),
+            inputs: Vec::new(),
+            frames: None,
+            replayable: false,
+            history: History::new(),
+            auto_shift: AutoShift::new(),
+            soft_drop: false,
+            score: 0,
+            level: rules.start_level,
+        };
+        game.start_mode();
+        game.enter_piece(first);
+        game.update_active_piece_coords();
+        game.save_placement();
+
+        Ok(game)
+    }
+
+    // pieces coming up after the active one, for callers outside of javascript
+    pub fn queue(&self) -> &[PieceType] {
+        &self.next_pieces
+    }
+
+    // None when the game was loaded from a save without its replay
+    pub fn replay(&self) -> Option<Replay> {
+        Some(Replay {
+            version: replay::REPLAY_VERSION,
+            width: self.width,
+            height: self.height - self.buffer_rows,
+            seed: self.seed,
+            rules: self.rules,
+            frames: self.frames.clone()?,
+        })
+    }
+
+    pub fn held(&self) -> Option<PieceType> {
+        self.held_type
+    }
+
+    // indexes into the visible board of the active piece's squares and its ground hint
+    pub fn active_piece_indexes(&self) -> &[u32] {
+        &self.active_piece_indexes
+    }
+
+    pub fn ground_hint_indexes(&self) -> &[u32] {
+        &self.ground_hint_indexes
+    }
+
+    // the whole board with the hidden rows, for bots that want to work with its bitboard
+    pub fn playfield(&self) -> &Board {
+        &self.board
+    }
+
+    // the board without the hidden rows, row by row from the top
+    pub fn visible_board(&self) -> &[Color] {
+        &self.board.colors()[self.buffer_rows * self.width..]
+    }
+
     fn get_index(&self, row: usize, col: usize) -> usize {
         (row * self.width) + col
     }
 
+    fn active_block(&self) -> &'static Block {
+        let piece = self.active_piece.piece;
+        self.rotation_system.block(piece.ttype, piece.state)
+    }
+
+    // keep the inputs sorted by time, inputs at the same time stay in the order they came in
+    fn queue_input(&mut self, event: Event, at: u64) {
+        let at = Duration::from_micros(at);
+        let idx = self.inputs.partition_point(|input| input.at <= at);
+        self.inputs.insert(idx, Input { at, event });
+    }
+
+    fn record_frame(&mut self, elapsed: u64) {
+        let Some(frames) = &mut self.frames else {
+            return;
+        };
+
+        let inputs = self.inputs.iter().map(|input| {
+            let (action, pressed) = match input.event {
+                Event::Press(action) => (action, true),
+                Event::Release(action) => (action, false),
+            };
+
+            RecordedInput { at: input.at.as_micros() as u64, action, pressed }
+        });
+
+        frames.push(Frame { elapsed, inputs: inputs.collect() });
+    }
+
+    fn apply(&mut self, event: Event) {
+        match event {
+            // nothing to move while the next piece is waiting to come in, but the auto shift charges
+            Event::Press(action) if self.entry_delay.is_some() => match action {
+                Action::MoveLeft => self.auto_shift.press(-1),
+                Action::MoveRight => self.auto_shift.press(1),
+                Action::SoftDrop => self.soft_drop = true,
+                _ => {}
+            },
+            Event::Press(action) => match action {
+                // the piece shifts by one column as soon as left or right is pressed,
+                // holding them down is handled by the auto shift in advance
+                Action::MoveLeft => {
+                    self.auto_shift.press(-1);
+                    self.shift(-1, 1);
+                }
+                Action::MoveRight => {
+                    self.auto_shift.press(1);
+                    self.shift(1, 1);
+                }
+                Action::SoftDrop => self.soft_drop = true,
+                Action::HardDrop => self.hard_drop(),
+                Action::RotateClockwise => self.rotate(Rotation::Clockwise),
+                Action::RotateCounterClockwise => self.rotate(Rotation::CounterClockwise),
+                Action::Hold => self.hold_piece(),
+            },
+            Event::Release(action) => match action {
+                Action::MoveLeft => self.auto_shift.release(-1),
+                Action::MoveRight => self.auto_shift.release(1),
+                Action::SoftDrop => self.soft_drop = false,
+                _ => {}
+            },
+        }
+    }
+
+    // run auto shift, gravity and the lock timer for a slice of the frame without any input in it
+    fn advance(&mut self, elapsed: Duration) {
+        // nothing that would happen after the time limit counts
+        let elapsed = self.time_left().map_or(elapsed, |left| elapsed.min(left));
+        self.game_time += elapsed;
+
+        self.rise_garbage(elapsed);
+        if self.state != State::Playing {
+            return;
+        }
+
+        let das = Duration::from_millis(self.rules.das_ms as u64);
+        let arr = Duration::from_millis(self.rules.arr_ms as u64);
+
+        let elapsed = match self.entry_delay {
+            Some(wait) if elapsed < wait => {
+                self.entry_delay = Some(wait - elapsed);
+                self.auto_shift.charge(elapsed, das, arr);
+                self.check_time();
+                return;
+            }
+            Some(wait) => {
+                self.entry_delay = None;
+                self.auto_shift.charge(wait, das, arr);
+                elapsed - wait
+            }
+            None => elapsed,
+        };
+
+        let direction = self.auto_shift.direction();
+        match self.auto_shift.charge(elapsed, das, arr) {
+            Some(0) => {}
+            Some(columns) => self.shift(direction, columns),
+            None => self.shift(direction, self.width),
+        }
+
+        // gravity can be faster than the updates, so the piece may have to fall several rows at once
+        let carried = self.elapsed;
+        self.elapsed += elapsed;
+        let fall_rate = self.current_fall_rate();
+        let instant = fall_rate.as_nanos() == 0 || self.is_twenty_g();
+        let gravity = if instant {
+            self.elapsed = Duration::from_micros(0);
+            self.height
+        } else {
+            let rows = self.elapsed.as_nanos() / fall_rate.as_nanos();
+            self.elapsed -= fall_rate * rows as u32;
+            rows.min(self.height as u128) as usize
+        };
+
+        // how far into the update the piece fell its last row, the lock delay only runs after that
+        let mut landed = Duration::from_micros(0);
+        if gravity > 0 {
+            let rows = self.fall(gravity);
+            if rows > 0 && !instant {
+                landed = (fall_rate * rows as u32).saturating_sub(carried).min(elapsed);
+            }
+
+            if self.soft_drop {
+                self.score += self.scoring_rule.soft_drop(rows);
+            }
+        }
+
+        // a piece on the ground isn't merged straight away, so the player still has
+        // the lock delay to slide it into the gaps in the middle of the board
+        let block = self.active_block();
+        if !self.can_fit_block(block, self.active_piece.x, self.active_piece.y + 1) {
+            // gravity doesn't build up while the piece is resting on the ground
+            self.elapsed = Duration::from_micros(0);
+            self.lock_elapsed += elapsed - landed;
+
+            if self.lock_elapsed >= self.lock_delay {
+                self.try_fuse_active_piece();
+            }
+        }
+
+        self.check_time();
+    }
+
+    // move the active piece straight to the ground and lock it
+    fn hard_drop(&mut self) {
+        let rows = self.fall(self.height);
+        self.score += self.scoring_rule.hard_drop(rows);
+
+        self.try_fuse_active_piece();
+    }
+
+    fn rotate(&mut self, rot: Rotation) {
+        let new_piece = match rot {
+            Rotation::Clockwise => self.active_piece.piece.rotate_clockwise(),
+            Rotation::CounterClockwise => self.active_piece.piece.rotate_counter_clockwise()
+        };
+        let ttype = new_piece.ttype;
+        let block = self.rotation_system.block(ttype, new_piece.state);
+        let kicks = self.rotation_system.kicks(ttype, self.active_piece.piece.state, new_piece.state);
+
+        // try each wall kick in order and take the first position the rotated piece fits in
+        for (i, (kick_x, kick_y)) in kicks.iter().enumerate() {
+            if i == 1 {
+                let (piece_x, piece_y) = (self.active_piece.x, self.active_piece.y);
+                let blocked = |x: usize, y: usize| {
+                    !self.can_fit_square(piece_x + x as isize, piece_y + y as isize)
+                };
+
+                if !self.rotation_system.can_kick(ttype, new_piece.state, &blocked) {
+                    break;
+                }
+            }
+
+            let x = self.active_piece.x + kick_x;
+            let y = self.active_piece.y + kick_y;
+
+            if self.can_fit_block(block, x, y) {
+                self.active_piece.piece = new_piece;
+                self.active_piece.x = x;
+                self.active_piece.y = y;
+                self.last_rotation = Some(i);
+                self.reset_lock();
+                break;
+            }
+        }
+    }
+
+    // swap the active piece with the held one, or with the next piece if nothing is held yet
+    fn hold_piece(&mut self) {
+        if !self.can_hold {
+            return;
+        }
+
+        let current_type = match self.held_type {
+            Some(held) => held,
+            None => {
+                let t = self.next_pieces[0];
+                self.next_pieces[0] = self.next_pieces[1];
+                self.next_pieces[1] = self.next_pieces[2];
+                self.next_pieces[2] = self.generator.next(&mut self.rng);
+
+                t
+            }
+        };
+
+        self.held_type = Some(self.active_piece.piece.ttype);
+        self.can_hold = false;
+        self.enter_piece(current_type);
+    }
+
+    // move the active piece sideways by up to the given columns, stopping at anything in the way
+    fn shift(&mut self, direction: isize, columns: usize) {
+        let block = self.active_block();
+
+        let mut moved = 0;
+        while moved < columns && self.can_fit_block(block, self.active_piece.x + direction, self.active_piece.y) {
+            self.active_piece.x += direction;
+            moved += 1;
+        }
+
+        if moved > 0 {
+            self.last_rotation = None;
+            self.reset_lock();
+        }
+    }
+
+    // pieces drop to the stack the moment they come in or move over a gap
+    fn is_twenty_g(&self) -> bool {
+        self.fall_rate <= level::TWENTY_G
+    }
+
+    fn current_fall_rate(&self) -> Duration {
+        match (self.soft_drop, self.rules.soft_drop_factor) {
+            (false, _) => self.fall_rate,
+            (true, 0) => Duration::from_micros(0),
+            (true, factor) => self.fall_rate / factor,
+        }
+    }
+
+    // move the active piece down by up to the given rows and return how many it actually moved
+    fn fall(&mut self, rows: usize) -> usize {
+        let block = self.active_block();
+
+        let mut moved = 0;
+        while moved < rows && self.can_fit_block(block, self.active_piece.x, self.active_piece.y + 1) {
+            self.active_piece.y += 1;
+            moved += 1;
+        }
+
+        if moved > 0 {
+            self.last_rotation = None;
+            self.reach_lowest_row();
+        }
+
+        moved
+    }
+
+    // falling below the lowest row so far always gives the piece a fresh lock timer
+    fn reach_lowest_row(&mut self) {
+        if self.active_piece.y > self.lowest_row {
+            self.lowest_row = self.active_piece.y;
+            self.lock_elapsed = Duration::from_micros(0);
+            self.lock_resets = 0;
+        }
+    }
+
+    // the player moved or rotated the active piece, which may restart its lock timer
+    fn reset_lock(&mut self) {
+        self.reach_lowest_row();
+
+        // nothing to restart if the piece hasn't been on the ground yet
+        if self.lock_elapsed == Duration::from_micros(0) {
+            return;
+        }
+
+        match self.rules.lock_policy {
+            LockPolicy::Infinite => self.lock_elapsed = Duration::from_micros(0),
+            LockPolicy::MoveReset => if self.lock_resets < self.rules.max_lock_resets {
+                self.lock_elapsed = Duration::from_micros(0);
+                self.lock_resets += 1;
+            },
+            LockPolicy::StepReset => {}
+        }
+    }
+
     fn update_active_piece_coords(&mut self) {
         self.active_piece_indexes.clear();
 
-        let block = self.active_piece.piece.block();
+        if self.entry_delay.is_some() {
+            return;
+        }
+
+        let block = self.active_block();
         let piece_x = self.active_piece.x;
         let piece_y = self.active_piece.y;
 
         for y in 0..4 {
             for x in 0..4 {
-                if block[y * 4 + x] == 1 {
+                if has_square(block, x, y) {
                     let x = piece_x + x as isize;
                     let y = piece_y + y as isize;
 
-                    if x < 0 || y < 0 {
+                    // squares in the hidden rows aren't drawn
+                    if x < 0 || y < self.buffer_rows as isize {
                         continue;
                     }
 
-                    let idx = self.get_index(y as usize, x as usize);
-                    self.active_piece_indexes.push(idx as u8);
+                    let idx = self.get_index(y as usize - self.buffer_rows, x as usize);
+                    self.active_piece_indexes.push(idx as u32);
                 }
             }
         }
@@ -359,11 +1124,11 @@ impl Game {
     fn update_ground_hint_coords(&mut self) {
         self.ground_hint_indexes.clear();
 
-        if self.state == State::Lost {
+        if self.state != State::Playing || self.entry_delay.is_some() {
             return;
         }
 
-        let block = self.active_piece.piece.block();
+        let block = self.active_block();
         let block_x = self.active_piece.x;
         let mut block_y = self.active_piece.y;
 
@@ -374,11 +1139,16 @@ impl Game {
 
         for y in 0..4 {
             for x in 0..4 {
-                if block[y * 4 + x] == 1 {
+                if has_square(block, x, y) {
                     let x = block_x + x as isize;
                     let y = block_y + y as isize;
-                    let idx = self.get_index(y as usize, x as usize);
-                    self.ground_hint_indexes.push(idx as u8);
+
+                    if y < self.buffer_rows as isize {
+                        continue;
+                    }
+
+                    let idx = self.get_index(y as usize - self.buffer_rows, x as usize);
+                    self.ground_hint_indexes.push(idx as u32);
                 }
             }
         }
@@ -387,227 +1157,226 @@ impl Game {
     // try fusing current active piece with the ground
     // return true if the active piece has successfully fused with the ground
     fn try_fuse_active_piece(&mut self) {
-        let block = self.active_piece.piece.block();
+        let block = self.active_block();
 
         if !self.can_fuse_active_piece(block) {
             return;
         }
 
+        self.record_page();
+        let tspin = self.detect_tspin();
+        let above_field = self.is_above_field(block);
         self.fuse_active_piece(block);
-        self.active_piece = Self::initialize_tetrimino(self.next_pieces[0]);
+        self.garbage_lines -= self.full_garbage_rows();
+        let lines = self.board.clear_lines();
+        self.score_line_clear(tspin, lines);
+
+        if self.check_goal() {
+            self.save_placement();
+            return;
+        }
+
+        if above_field && self.rules.lock_out {
+            self.lose(TopOut::LockOut);
+            self.save_placement();
+            return;
+        }
+
+        self.enter_piece(self.next_pieces[0]);
         self.next_pieces[0] = self.next_pieces[1];
         self.next_pieces[1] = self.next_pieces[2];
-        self.next_pieces[2] = self.generator.next().unwrap();
+        self.next_pieces[2] = self.generator.next(&mut self.rng);
         self.can_hold = true;
-    }
 
-    fn check_game_over(&mut self) {
-        // game ending state, hack around the fact that the pieces don't have runway space
-        // before the grid by moving them up square by square
-        let block = self.active_piece.piece.block();
-        if self.is_fused_in_ground(block, self.active_piece.x, self.active_piece.y) {
-            self.active_piece.y -= 1;
-            self.state = State::Lost;
+        let mut delay = Duration::from_millis(self.rules.are_ms as u64);
+        if lines > 0 {
+            delay += Duration::from_millis(self.rules.line_clear_delay_ms as u64);
         }
+        self.entry_delay = Some(delay).filter(|delay| !delay.is_zero());
 
-        if self.is_fused_in_ground(block, self.active_piece.x, self.active_piece.y) {
-            self.active_piece.y -= 1;
-            self.state = State::Lost;
-        }
+        self.save_placement();
     }
 
-    fn can_fuse_active_piece(&self, block: &'static Block) -> bool {
-        let piece_x = self.active_piece.x;
-        let piece_y = self.active_piece.y;
+    fn score_line_clear(&mut self, tspin: TSpin, lines: usize) {
+        let mut clear = LineClear {
+            tspin,
+            lines,
+            perfect_clear: lines > 0 && self.board.is_empty(),
+            ..LineClear::empty()
+        };
 
-        for y in 0..4 {
-            for x in 0..4 {
-                if block[y * 4 + x] == 1 {
-                    // check the next row, current col if there's any occupied piece
-                    let check_y = piece_y + y as isize; // coord y on the board
-                    let check_x = piece_x + x as isize; // coord x
-
-                    // is the current piece on the last row of the board?
-                    if check_y == self.height as isize - 1 {
-                        return true;
-                    }
+        if lines > 0 {
+            let combo = self.combo.map_or(0, |combo| combo + 1);
+            clear.combo = combo;
+            self.combo = Some(combo);
 
-                    // is the next row not empty?
-                    let idx = self.get_index(check_y as usize + 1, check_x as usize);
-                    if *self.board.get(idx).unwrap() != Color::None {
-                        return true;
-                    }
-                }
-            }
+            clear.back_to_back = self.back_to_back && clear.is_difficult();
+            self.back_to_back = clear.is_difficult();
+        } else {
+            self.combo = None;
         }
 
-        false
+        self.score += self.scoring_rule.line_clear(&clear, self.level);
+        self.lines += lines;
+        self.last_clear = clear;
+        self.level_up(&clear);
     }
 
-    fn fuse_active_piece(&mut self, block: &'static Block) {
-        let color = self.active_piece.piece.color();
-        let piece_x = self.active_piece.x;
-        let piece_y = self.active_piece.y;
-
-        for y in 0..4 {
-            for x in 0..4 {
-                if block[y * 4 + x] == 1 {
-                    let x = piece_x + x as isize;
-                    let y = piece_y + y as isize;
-                    let idx = self.get_index(y as usize, x as usize);
-                    self.board[idx] = color;
-                }
-            }
+    fn level_up(&mut self, clear: &LineClear) {
+        if self.rules.mode == Mode::Master {
+            self.master_level_up(clear.lines);
+            self.fall_rate = self.rules.gravity.fall_rate(self.level);
+            return;
         }
-    }
 
-    fn can_fit_block(&self, block: &'static Block, x: isize, y: isize) -> bool {
-        for block_y in 0..4 {
-            for block_x in 0..4 {
-                if block[block_y * 4 + block_x] == 1 {
-                    let board_x = x + block_x as isize;
-                    let board_y = y + block_y as isize;
-
-                    if board_x < 0
-                        || board_y < 0
-                        || board_x == self.width as isize
-                        || board_y == self.height as isize {
-                        return false;
-                    }
+        let goal = self.rules.level_goal;
+        let mut awarded = goal.awarded(clear);
 
-                    let idx = self.get_index(board_y as usize, board_x as usize);
-                    if self.board[idx] != Color::None {
-                        return false;
-                    }
-                }
-            }
+        // a big clear can be worth more than one level
+        while awarded >= self.goal_remaining {
+            awarded -= self.goal_remaining;
+            self.level += 1;
+            self.goal_remaining = goal.goal(self.level);
         }
+        self.goal_remaining -= awarded;
 
-        true
+        self.fall_rate = self.rules.gravity.fall_rate(self.level);
     }
 
-    fn is_fused_in_ground(&self, block: &'static Block, x: isize, y: isize) -> bool {
-        for block_y in 0..4 {
-            for block_x in 0..4 {
-                if block[block_y * 4 + block_x] == 1 {
-                    let board_x = x + block_x as isize;
-                    let board_y = y + block_y as isize;
-
-                    if board_x >= 0 && board_x < self.width as isize
-                        && board_y >= 0 && board_y < self.height as isize {
-                        let idx = self.get_index(board_y as usize, board_x as usize);
-                        if self.board[idx] != Color::None {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
+    // only a T that was rotated into place can spin
+    fn detect_tspin(&self) -> TSpin {
+        let kick = match self.last_rotation {
+            Some(kick) if self.active_piece.piece.ttype == PieceType::T => kick,
+            _ => return TSpin::None,
+        };
 
-        false
+        let (piece_x, piece_y) = (self.active_piece.x, self.active_piece.y);
+        let occupied = |x: isize, y: isize| !self.can_fit_square(piece_x + x, piece_y + y);
+
+        tspin::detect(self.active_block(), kick, &occupied)
     }
 
-    fn erase_lines(&mut self) {
-        let lines = self.check_erasable_lines();
-        self.score += lines.len() * 10;
+    fn lose(&mut self, reason: TopOut) {
+        self.state = State::Lost;
+        self.top_out = reason;
+    }
 
-        for line in lines {
-            for y in (0..line).rev() {
-                for x in 0..self.width {
-                    let idx = self.get_index(y, x);
-                    let next_idx = self.get_index(y + 1, x);
+    // whether every square of the active piece is in the hidden rows
+    fn is_above_field(&self, block: &'static Block) -> bool {
+        let bottom = (0..4).rev()
+            .find(|y| block[*y] != 0)
+            .unwrap_or(0);
 
-                    self.board[next_idx] = self.board[idx];
-                }
-            }
+        self.active_piece.y + (bottom as isize) < self.buffer_rows as isize
+    }
 
-            for x in 0..self.width {
-                let idx = self.get_index(0, x);
-                self.board[idx] = Color::None;
-            }
+    // garbage sent from outside of the game isn't an input, so the game can't be replayed after it
+    pub fn add_garbage_row(&mut self, hole: usize) {
+        if self.state == State::Playing {
+            self.frames = None;
         }
+        self.push_garbage_row(hole);
     }
 
-    fn check_erasable_lines(&self) -> Vec<usize> {
-        let mut lines = Vec::new();
-
-        for row in 0..self.height {
-            if self.can_erase_row(row) {
-                lines.push(row);
-            }
+    // push the whole stack up by a row and fill the bottom one with garbage except for the hole,
+    // the active piece moves up with it if the new row runs into it
+    pub(crate) fn push_garbage_row(&mut self, hole: usize) {
+        if self.state != State::Playing {
+            return;
         }
 
-        lines
-    }
+        let garbage_out = self.board.push_garbage(hole);
+        self.garbage_lines = (self.garbage_lines + 1).min(self.height);
 
-    fn can_erase_row(&self, row: usize) -> bool {
-        for x in 0..self.width {
-            let idx = self.get_index(row, x);
-            if self.board[idx] == Color::None {
-                return false;
-            }
+        let block = self.active_block();
+        if self.is_fused_in_ground(block, self.active_piece.x, self.active_piece.y) {
+            self.active_piece.y -= 1;
+            self.lowest_row -= 1;
         }
 
-        true
+        if garbage_out && self.rules.garbage_out {
+            self.lose(TopOut::GarbageOut);
+        }
+
+        self.update_active_piece_coords();
+        self.update_ground_hint_coords();
     }
 
-    // apply displacements to move the next active tetrimino into the center of board
-    fn initialize_tetrimino(ttype: PieceType) -> ActivePiece {
-        let (x, y) = match ttype {
-            PieceType::T => (3, -1),
-            PieceType::J => (4, 0),
-            _ => (3, 0)
-        };
+    // rows of garbage that are full and about to be cleared
+    fn full_garbage_rows(&self) -> usize {
+        let rows = self.board.rows();
+        rows[rows.len() - self.garbage_lines..].iter()
+            .filter(|row| **row == self.board.full_row())
+            .count()
+    }
 
-        let current_tetrimino = ActivePiece {
-            piece: Tetrimino::from(ttype),
-            x,
-            y,
-        };
+    // the active piece is resting on the stack or the floor
+    fn can_fuse_active_piece(&self, block: &'static Block) -> bool {
+        self.board.collides(block, self.active_piece.x, self.active_piece.y + 1)
+    }
 
-        current_tetrimino
+    fn fuse_active_piece(&mut self, block: &'static Block) {
+        let color = self.active_piece.piece.color();
+        self.board.place(block, self.active_piece.x, self.active_piece.y, color);
     }
-}
 
+    fn can_fit_block(&self, block: &'static Block, x: isize, y: isize) -> bool {
+        !self.board.collides(block, x, y)
+    }
 
-// Random generator to generate permutation of 7 pieces of tetriminos
-// according to the guideline here:
-// https://tetris.fandom.com/wiki/Random_Generator
-struct SevenGenerator {
-    shuffled: [PieceType; 7],
-    index: usize,
-}
+    // whether the square at x, y is inside the board and empty
+    fn can_fit_square(&self, x: isize, y: isize) -> bool {
+        !self.board.is_occupied(x, y)
+    }
 
-impl SevenGenerator {
-    fn new() -> SevenGenerator {
-        use PieceType::*;
+    fn is_fused_in_ground(&self, block: &'static Block, x: isize, y: isize) -> bool {
+        self.board.overlaps(block, x, y)
+    }
+
+    // make a new piece of the given type the active one with a fresh lock timer
+    fn enter_piece(&mut self, ttype: PieceType) {
+        self.active_piece = self.spawn(ttype);
 
-        let mut shuffled = [I, O, T, S, Z, J, L];
-        utils::shuffle(&mut shuffled);
+        let block = self.active_block();
+        let (x, y) = (self.active_piece.x, self.active_piece.y);
+        if self.is_fused_in_ground(block, x, y) {
+            // the first free spot above the spawn position, if there's still room in the board
+            let above = (1..=y + 4).map(|up| y - up).find(|&y| self.can_fit_block(block, x, y));
 
-        SevenGenerator {
-            shuffled,
-            index: 0,
+            match above {
+                Some(y) if !self.rules.block_out => self.active_piece.y = y,
+                _ => self.lose(TopOut::BlockOut),
+            }
+        } else if self.is_twenty_g() {
+            self.fall(self.height);
+        } else if self.buffer_rows >= 2 && self.can_fit_block(block, x, y + 1) {
+            // like the guideline games the piece moves down a row straight away if it can,
+            // so it peeks into the visible field the moment it appears
+            self.active_piece.y += 1;
         }
-    }
-}
 
-impl Iterator for SevenGenerator {
-    type Item = PieceType;
+        self.last_rotation = None;
+        self.lock_elapsed = Duration::from_micros(0);
+        self.lock_resets = 0;
+        self.lowest_row = self.active_piece.y;
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index == 7 {
-            use PieceType::*;
+    // pieces enter in the two hidden rows right above the visible field
+    fn spawn(&self, ttype: PieceType) -> ActivePiece {
+        let top = (self.buffer_rows as isize - 2).max(0);
+        Self::initialize_tetrimino(&*self.rotation_system, self.width, top, ttype)
+    }
 
-            let mut shuffled = [I, O, T, S, Z, J, L];
-            utils::shuffle(&mut shuffled);
+    // place the next active tetrimino where the rotation system wants it to enter the board,
+    // the rotation system positions it against the top row and `top` moves it down from there
+    fn initialize_tetrimino(rotation_system: &dyn RotationSystem, width: usize, top: isize,
+                            ttype: PieceType) -> ActivePiece {
+        let (x, y) = rotation_system.spawn_position(ttype, width);
 
-            self.shuffled = shuffled;
-            self.index = 0;
+        ActivePiece {
+            piece: Tetrimino::from(ttype, rotation_system.spawn_state(ttype)),
+            x,
+            y: y + top,
         }
-
-        let result = self.shuffled[self.index];
-        self.index += 1;
-        Some(result)
     }
-}
\ No newline at end of file
+}
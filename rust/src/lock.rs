@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// what buys a piece resting on the ground more time before it locks
+// https://tetris.wiki/Lock_delay
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockPolicy {
+    // every move or rotation restarts the timer, the piece can be kept alive forever
+    Infinite,
+    // moves and rotations restart the timer up to a limited number of times per piece,
+    // the count starts over when the piece falls below its lowest row so far
+    MoveReset,
+    // only falling below the lowest row so far restarts the timer, like the TGM games
+    StepReset,
+}
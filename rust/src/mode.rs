@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::grade::GM_CHECKPOINTS;
+use crate::rng::Rng;
+use crate::{Game, State};
+
+// a split time is recorded every time this many more lines are cleared
+pub const SPLIT_LINES: usize = 10;
+
+// every row of garbage in survival rises this much sooner than the one before,
+// down to a quarter of the interval it started with
+const GARBAGE_SPEEDUP: u32 = 20;
+const MIN_GARBAGE_INTERVAL: u32 = 4;
+
+// master ends at this level, section times are taken every SECTION_LEVELS levels
+pub const MASTER_LEVEL: usize = 999;
+pub const SECTION_LEVELS: usize = 100;
+
+// What the player is going for. Marathon goes on until the stack tops out, the other modes
+// also end once their goal is reached, with the targets they go for set in the rules.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mode {
+    Marathon,
+    // clear line_target lines as fast as possible
+    Sprint,
+    // score as much as possible before time_limit_ms runs out
+    Ultra,
+    // clear garbage_rows rows of garbage the game starts with
+    Dig,
+    // stay alive while garbage rises every garbage_interval_ms, a little sooner every time
+    Survival,
+    // TGM's master mode, levels go up with every piece and every line up to MASTER_LEVEL
+    // and the game is graded on the way, see Rules::master for its timings
+    Master,
+}
+
+// where the holes go in the rows of garbage dig starts with and survival pushes up
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GarbageHoles {
+    // any column in every row
+    Random,
+    // never in the same column as the row below
+    Messy,
+    // in the same column as the row below most of the time, so holes line up in streaks
+    Clean,
+}
+
+impl GarbageHoles {
+    // column of the hole in the next row up, previous is the hole of the row below it
+    pub fn next(self, previous: Option<usize>, width: usize, rng: &mut Rng) -> usize {
+        match (self, previous) {
+            (GarbageHoles::Messy, Some(previous)) => (previous + 1 + rng.below(width - 1)) % width,
+            // a streak breaks one row in eight
+            (GarbageHoles::Clean, Some(previous)) if rng.below(8) != 0 => previous,
+            _ => rng.below(width),
+        }
+    }
+}
+
+impl Game {
+    // set up the board for the mode before the first piece comes in,
+    // the garbage is dealt from the game's rng so everyone racing the same seed digs the same rows
+    pub(crate) fn start_mode(&mut self) {
+        self.garbage_lines = 0;
+        self.garbage_hole = None;
+        self.garbage_timer = Duration::from_micros(0);
+        self.garbage_interval = self.first_garbage_interval();
+
+        if self.rules.mode != Mode::Dig {
+            return;
+        }
+
+        let rows = self.rules.garbage_rows.min(self.height - self.buffer_rows);
+        for _ in 0..rows {
+            let hole = self.rules.garbage_holes.next(self.garbage_hole, self.width, &mut self.rng);
+            self.board.push_garbage(hole);
+            self.garbage_hole = Some(hole);
+        }
+        self.garbage_lines = rows;
+    }
+
+    pub(crate) fn first_garbage_interval(&self) -> Duration {
+        Duration::from_millis(self.rules.garbage_interval_ms.max(1) as u64)
+    }
+
+    // survival pushes a row of garbage up every time its timer runs out
+    pub(crate) fn rise_garbage(&mut self, elapsed: Duration) {
+        if self.rules.mode != Mode::Survival {
+            return;
+        }
+
+        let min = self.first_garbage_interval() / MIN_GARBAGE_INTERVAL;
+        self.garbage_interval = self.garbage_interval.max(min);
+        self.garbage_timer += elapsed;
+        while self.state == State::Playing && self.garbage_timer >= self.garbage_interval {
+            self.garbage_timer -= self.garbage_interval;
+            self.garbage_interval = (self.garbage_interval - self.garbage_interval / GARBAGE_SPEEDUP).max(min);
+
+            let hole = self.rules.garbage_holes.next(self.garbage_hole, self.width, &mut self.rng);
+            self.garbage_hole = Some(hole);
+            self.push_garbage_row(hole);
+        }
+    }
+
+    // called after every lock, returns true when the game just finished
+    pub(crate) fn check_goal(&mut self) -> bool {
+        while self.splits.len() < self.lines / SPLIT_LINES {
+            self.splits.push(self.game_time);
+        }
+
+        let done = match self.rules.mode {
+            Mode::Sprint => self.lines >= self.rules.line_target,
+            Mode::Dig => self.garbage_lines == 0,
+            Mode::Master => self.level >= MASTER_LEVEL,
+            Mode::Marathon | Mode::Ultra | Mode::Survival => false,
+        };
+
+        if done {
+            self.state = State::Finished;
+        }
+        done
+    }
+
+    // time left before the game ends, None in modes without a time limit
+    pub(crate) fn time_left(&self) -> Option<Duration> {
+        match self.rules.mode {
+            Mode::Ultra => Some(Duration::from_millis(self.rules.time_limit_ms as u64).saturating_sub(self.game_time)),
+            Mode::Marathon | Mode::Sprint | Mode::Dig | Mode::Survival | Mode::Master => None,
+        }
+    }
+
+    pub(crate) fn check_time(&mut self) {
+        if self.state == State::Playing && self.time_left() == Some(Duration::from_micros(0)) {
+            self.state = State::TimeUp;
+        }
+    }
+
+    // every piece takes master up a level except the last one of a section, which waits for
+    // a line clear to get past it, and every line cleared counts as a level too
+    pub(crate) fn master_level_up(&mut self, lines: usize) {
+        let before = self.level;
+        self.level = (self.level + lines).min(MASTER_LEVEL);
+        if self.level % SECTION_LEVELS != SECTION_LEVELS - 1 && self.level < MASTER_LEVEL - 1 {
+            self.level += 1;
+        }
+
+        // the last section ends at MASTER_LEVEL instead of the next hundred
+        let sections = if self.level >= MASTER_LEVEL {
+            MASTER_LEVEL / SECTION_LEVELS + 1
+        } else {
+            self.level / SECTION_LEVELS
+        };
+        while self.sections.len() < sections {
+            self.sections.push(self.game_time);
+        }
+
+        for (level, score, time) in GM_CHECKPOINTS {
+            if before < level && self.level >= level {
+                self.gm_qualified &= self.score >= score && self.game_time <= time;
+            }
+        }
+    }
+}
@@ -0,0 +1,441 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::rng::Rng;
+use crate::tetrimino::PieceType;
+use crate::utils;
+
+// A randomizer picks the next piece of the queue. All of the randomness comes from
+// the rng it's handed, so the same seed always deals the same pieces.
+pub trait Randomizer {
+    fn next(&mut self, rng: &mut Rng) -> PieceType;
+
+    // forget everything dealt so far, used when the game restarts
+    fn reset(&mut self);
+
+    // what the randomizer remembers as plain data, so a saved game deals the same pieces
+    // once it's loaded again, randomizers that don't remember anything can keep the defaults
+    fn save(&self) -> RandomizerState {
+        RandomizerState::None
+    }
+
+    // pick up from a saved state, false if it was saved by a different kind of randomizer
+    // or isn't one this randomizer could have gotten into
+    fn load(&mut self, state: &RandomizerState) -> bool {
+        *state == RandomizerState::None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RandomizerState {
+    None,
+    // pieces left in the bag
+    Bag(Vec<PieceType>),
+    // the piece dealt last
+    Last(Option<PieceType>),
+    // the last four pieces dealt and whether the first piece is still to come
+    History {
+        history: [PieceType; 4],
+        first: bool,
+    },
+    Pool {
+        pool: Vec<PieceType>,
+        history: [PieceType; 4],
+        drought: Vec<PieceType>,
+        first: bool,
+    },
+    // pieces set up to come first and the randomizer dealing after them
+    Preset {
+        pieces: Vec<PieceType>,
+        then: Box<RandomizerState>,
+    },
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RandomizerKind {
+    SevenBag,
+    FourteenBag,
+    SevenPlusOne,
+    Random,
+    Nes,
+    Tgm,
+    Tgm3,
+}
+
+impl RandomizerKind {
+    pub fn build(self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(Bag::new(1, 0)),
+            RandomizerKind::FourteenBag => Box::new(Bag::new(2, 0)),
+            RandomizerKind::SevenPlusOne => Box::new(Bag::new(1, 1)),
+            RandomizerKind::Random => Box::new(PureRandom),
+            RandomizerKind::Nes => Box::new(NesRandomizer::new()),
+            RandomizerKind::Tgm => Box::new(TgmRandomizer::new()),
+            RandomizerKind::Tgm3 => Box::new(Tgm3Randomizer::new()),
+        }
+    }
+}
+
+// Shuffles a bag with every piece in it some number of times, plus a few extra random
+// pieces, and deals the bag out before shuffling the next one.
+// One copy is the guideline's 7-bag, two copies is a 14-bag and one copy with an extra
+// piece is 7+1.
+// https://tetris.wiki/Random_Generator
+pub struct Bag {
+    copies: usize,
+    extra: usize,
+    pieces: Vec<PieceType>,
+}
+
+impl Bag {
+    // a bag with nothing in it deals a single random piece at a time instead
+    pub fn new(copies: usize, extra: usize) -> Bag {
+        Bag {
+            copies,
+            extra: if copies == 0 { extra.max(1) } else { extra },
+            pieces: Vec::new(),
+        }
+    }
+
+    fn refill(&mut self, rng: &mut Rng) {
+        for _ in 0..self.copies {
+            self.pieces.extend_from_slice(&PieceType::ALL);
+        }
+
+        for _ in 0..self.extra {
+            self.pieces.push(PieceType::ALL[rng.below(7)]);
+        }
+
+        utils::shuffle(&mut self.pieces, rng);
+    }
+}
+
+impl Randomizer for Bag {
+    fn next(&mut self, rng: &mut Rng) -> PieceType {
+        if self.pieces.is_empty() {
+            self.refill(rng);
+        }
+
+        self.pieces.pop().unwrap()
+    }
+
+    fn reset(&mut self) {
+        self.pieces.clear();
+    }
+
+    fn save(&self) -> RandomizerState {
+        RandomizerState::Bag(self.pieces.clone())
+    }
+
+    fn load(&mut self, state: &RandomizerState) -> bool {
+        match state {
+            // no more of any piece than a full bag holds
+            RandomizerState::Bag(pieces) => {
+                let most = self.copies + self.extra;
+                if PieceType::ALL.iter().any(|ttype| pieces.iter().filter(|p| *p == ttype).count() > most) {
+                    return false;
+                }
+                self.pieces = pieces.clone();
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+// every piece is equally likely every time, droughts and floods included
+pub struct PureRandom;
+
+impl Randomizer for PureRandom {
+    fn next(&mut self, rng: &mut Rng) -> PieceType {
+        PieceType::ALL[rng.below(7)]
+    }
+
+    fn reset(&mut self) {}
+}
+
+// The NES rolls an eighth "piece" besides the seven, rolling that or the same piece
+// as last time makes it roll once more and take whatever comes out
+// https://tetris.wiki/Tetris_(NES,_Nintendo)#Randomizer
+pub struct NesRandomizer {
+    last: Option<PieceType>,
+}
+
+impl NesRandomizer {
+    pub fn new() -> NesRandomizer {
+        NesRandomizer { last: None }
+    }
+}
+
+impl Default for NesRandomizer {
+    fn default() -> Self {
+        NesRandomizer::new()
+    }
+}
+
+impl Randomizer for NesRandomizer {
+    fn next(&mut self, rng: &mut Rng) -> PieceType {
+        let roll = rng.below(8);
+
+        let piece = if roll == 7 || Some(PieceType::ALL[roll]) == self.last {
+            PieceType::ALL[rng.below(7)]
+        } else {
+            PieceType::ALL[roll]
+        };
+
+        self.last = Some(piece);
+        piece
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+
+    fn save(&self) -> RandomizerState {
+        RandomizerState::Last(self.last)
+    }
+
+    fn load(&mut self, state: &RandomizerState) -> bool {
+        match state {
+            RandomizerState::Last(last) => self.last = *last,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+// pieces that are hard to start a game with, the TGM randomizers never deal these first
+const FIRST_PIECES: [PieceType; 4] = [PieceType::I, PieceType::J, PieceType::L, PieceType::T];
+
+// TGM2 keeps the last four pieces and rolls up to six times for one that isn't among them,
+// the history starts full of S and Z so those are unlikely early on
+// https://tetris.wiki/TGM_randomizer
+pub struct TgmRandomizer {
+    history: [PieceType; 4],
+    first: bool,
+}
+
+impl TgmRandomizer {
+    const ROLLS: usize = 6;
+
+    pub fn new() -> TgmRandomizer {
+        use PieceType::*;
+
+        TgmRandomizer {
+            history: [Z, S, S, Z],
+            first: true,
+        }
+    }
+}
+
+impl Default for TgmRandomizer {
+    fn default() -> Self {
+        TgmRandomizer::new()
+    }
+}
+
+impl Randomizer for TgmRandomizer {
+    fn next(&mut self, rng: &mut Rng) -> PieceType {
+        let piece = if self.first {
+            self.first = false;
+            FIRST_PIECES[rng.below(FIRST_PIECES.len())]
+        } else {
+            let mut piece = PieceType::ALL[rng.below(7)];
+            for _ in 1..Self::ROLLS {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = PieceType::ALL[rng.below(7)];
+            }
+            piece
+        };
+
+        self.history.rotate_left(1);
+        self.history[3] = piece;
+        piece
+    }
+
+    fn reset(&mut self) {
+        *self = TgmRandomizer::new();
+    }
+
+    fn save(&self) -> RandomizerState {
+        RandomizerState::History {
+            history: self.history,
+            first: self.first,
+        }
+    }
+
+    fn load(&mut self, state: &RandomizerState) -> bool {
+        match state {
+            RandomizerState::History { history, first } => {
+                self.history = *history;
+                self.first = *first;
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+// TGM3 rolls from a pool of 35 pieces, five of each, and every pick is put back into
+// the pool as the piece that hasn't been dealt for the longest, so droughts fix themselves
+// https://tetris.wiki/TGM_randomizer#TGM3
+pub struct Tgm3Randomizer {
+    pool: Vec<PieceType>,
+    history: [PieceType; 4],
+
+    // pieces from the longest unseen to the most recently dealt
+    drought: Vec<PieceType>,
+    first: bool,
+}
+
+impl Tgm3Randomizer {
+    const ROLLS: usize = 6;
+    const POOL_SIZE: usize = 35;
+
+    pub fn new() -> Tgm3Randomizer {
+        use PieceType::*;
+
+        let mut pool = Vec::new();
+        for _ in 0..Self::POOL_SIZE / 7 {
+            pool.extend_from_slice(&PieceType::ALL);
+        }
+
+        Tgm3Randomizer {
+            pool,
+            history: [S, Z, S, Z],
+            drought: vec![J, I, Z, L, O, T, S],
+            first: true,
+        }
+    }
+
+    fn deal(&mut self, piece: PieceType) -> PieceType {
+        self.drought.retain(|p| *p != piece);
+        self.drought.push(piece);
+
+        self.history.rotate_left(1);
+        self.history[3] = piece;
+        piece
+    }
+}
+
+impl Default for Tgm3Randomizer {
+    fn default() -> Self {
+        Tgm3Randomizer::new()
+    }
+}
+
+impl Randomizer for Tgm3Randomizer {
+    fn next(&mut self, rng: &mut Rng) -> PieceType {
+        if self.first {
+            self.first = false;
+            let piece = FIRST_PIECES[rng.below(FIRST_PIECES.len())];
+            return self.deal(piece);
+        }
+
+        let mut idx = rng.below(self.pool.len());
+        for _ in 1..Self::ROLLS {
+            if !self.history.contains(&self.pool[idx]) {
+                break;
+            }
+
+            // a rejected piece makes room for the one in the longest drought
+            self.pool[idx] = self.drought[0];
+            idx = rng.below(self.pool.len());
+        }
+
+        let piece = self.pool[idx];
+        self.deal(piece);
+        self.pool[idx] = self.drought[0];
+        piece
+    }
+
+    fn reset(&mut self) {
+        *self = Tgm3Randomizer::new();
+    }
+
+    fn save(&self) -> RandomizerState {
+        RandomizerState::Pool {
+            pool: self.pool.clone(),
+            history: self.history,
+            drought: self.drought.clone(),
+            first: self.first,
+        }
+    }
+
+    fn load(&mut self, state: &RandomizerState) -> bool {
+        match state {
+            RandomizerState::Pool { pool, history, drought, first } => {
+                // the pool only ever swaps pieces and the drought has every piece once
+                let is_drought = drought.len() == 7 && PieceType::ALL.iter().all(|ttype| drought.contains(ttype));
+                if pool.len() != Self::POOL_SIZE || !is_drought {
+                    return false;
+                }
+
+                self.pool = pool.clone();
+                self.history = *history;
+                self.drought = drought.clone();
+                self.first = *first;
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+// Deals a fixed list of pieces first and then carries on with another randomizer,
+// for games that start from a set up position with a known queue.
+pub struct Preset {
+    pieces: VecDeque<PieceType>,
+    then: Box<dyn Randomizer>,
+}
+
+impl Preset {
+    pub fn new(pieces: Vec<PieceType>, then: Box<dyn Randomizer>) -> Preset {
+        Preset {
+            pieces: pieces.into(),
+            then,
+        }
+    }
+}
+
+impl Randomizer for Preset {
+    fn next(&mut self, rng: &mut Rng) -> PieceType {
+        self.pieces.pop_front().unwrap_or_else(|| self.then.next(rng))
+    }
+
+    // a restarted game doesn't start from the set up position, so the preset pieces go too
+    fn reset(&mut self) {
+        self.pieces.clear();
+        self.then.reset();
+    }
+
+    fn save(&self) -> RandomizerState {
+        RandomizerState::Preset {
+            pieces: self.pieces.iter().copied().collect(),
+            then: Box::new(self.then.save()),
+        }
+    }
+
+    fn load(&mut self, state: &RandomizerState) -> bool {
+        match state {
+            RandomizerState::Preset { pieces, then } => {
+                self.pieces = pieces.iter().copied().collect();
+                self.then.load(then)
+            }
+            state => {
+                self.pieces.clear();
+                self.then.load(state)
+            }
+        }
+    }
+}
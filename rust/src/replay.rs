@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::input::Action;
+use crate::{Game, Rules};
+
+// bumped whenever the format changes in a way older replays can't be read with
+pub const REPLAY_VERSION: u32 = 1;
+
+// Everything needed to play a game again exactly as it went: the board size, rules and seed
+// it was created with and every update it got along with the inputs queued for it.
+// Games dealing from a randomizer that isn't one of the RandomizerKinds, that got garbage
+// from outside of the game or that used undo can't be replayed and don't have one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub rules: Rules,
+    pub frames: Vec<Frame>,
+}
+
+// one call to update, elapsed and the input times are in microseconds like the game takes them
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Frame {
+    pub elapsed: u64,
+    pub inputs: Vec<RecordedInput>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub at: u64,
+    pub action: Action,
+    // false for a release
+    pub pressed: bool,
+}
+
+impl Replay {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("replays only hold plain data")
+    }
+
+    pub fn from_json(json: &str) -> Result<Replay, Error> {
+        let replay: Replay = serde_json::from_str(json)
+            .map_err(|e| Error::InvalidReplay(e.to_string()))?;
+
+        if replay.version != REPLAY_VERSION {
+            return Err(Error::InvalidReplay(format!("unsupported replay version {}", replay.version)));
+        }
+
+        Ok(replay)
+    }
+
+    // play the whole replay and return the game as it was at the end of it
+    pub fn play(&self) -> Result<Game, Error> {
+        let mut player = ReplayPlayer::new(self.clone())?;
+        while player.step() {}
+
+        Ok(player.game)
+    }
+}
+
+// Plays a replay back one update at a time, for watching it instead of jumping to the end.
+pub struct ReplayPlayer {
+    replay: Replay,
+    game: Game,
+    frame: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Result<ReplayPlayer, Error> {
+        let game = Game::with_rules(replay.width, replay.height, replay.rules, replay.seed)?;
+
+        Ok(ReplayPlayer { replay, game, frame: 0 })
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.replay.frames.len()
+    }
+
+    // feed the next frame to the game, returns false once there's nothing left to play
+    pub fn step(&mut self) -> bool {
+        let frame = match self.replay.frames.get(self.frame) {
+            Some(frame) => frame,
+            None => return false,
+        };
+
+        for input in &frame.inputs {
+            if input.pressed {
+                self.game.press(input.action, input.at);
+            } else {
+                self.game.release(input.action, input.at);
+            }
+        }
+        self.game.update(frame.elapsed);
+        self.frame += 1;
+
+        true
+    }
+}
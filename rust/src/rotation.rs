@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::tetrimino::{Block, PieceType};
+
+pub use ars::Ars;
+pub use nes::Nes;
+pub use srs::Srs;
+
+mod ars;
+mod nes;
+mod srs;
+
+// x, y offset to try when a rotation doesn't fit in place
+pub type Kick = (isize, isize);
+
+// A rotation system owns what the pieces look like in each of their four rotation states,
+// where they enter the board and which wall kicks are tried when a rotation is blocked.
+// Rotation states are numbered clockwise from the spawn state, 0, R, 2, L in guideline terms.
+pub trait RotationSystem {
+    fn block(&self, ttype: PieceType, state: usize) -> &'static Block;
+
+    // rotation state a piece is in when it enters the board
+    fn spawn_state(&self, _ttype: PieceType) -> usize {
+        0
+    }
+
+    // x, y coordinate of the piece's 4x4 box when it enters a board of the given width,
+    // y puts the top of the piece on the first row of the spawn area
+    fn spawn_position(&self, ttype: PieceType, width: usize) -> (isize, isize);
+
+    // offsets to test in order when rotating from one state to another,
+    // the first one is always the unkicked position
+    fn kicks(&self, ttype: PieceType, from: usize, to: usize) -> &'static [Kick];
+
+    // some systems decide whether to kick at all by where the rotated piece is blocked,
+    // `blocked` tells if the square at x, y of the piece's 4x4 box is out of bounds or taken
+    fn can_kick(&self, _ttype: PieceType, _to: usize, _blocked: &dyn Fn(usize, usize) -> bool) -> bool {
+        true
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RotationSystemKind {
+    Srs,
+    Ars,
+    Nes,
+}
+
+impl RotationSystemKind {
+    pub fn build(self) -> Box<dyn RotationSystem> {
+        match self {
+            RotationSystemKind::Srs => Box::new(Srs),
+            RotationSystemKind::Ars => Box::new(Ars),
+            RotationSystemKind::Nes => Box::new(Nes),
+        }
+    }
+}
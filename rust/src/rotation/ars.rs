@@ -0,0 +1,225 @@
+use crate::rotation::{Kick, RotationSystem};
+use crate::tetrimino::{block, has_square, Block, PieceType};
+
+// Arika Rotation System from the TGM series, pieces sit at the bottom of their box
+// and only kick one column right or left
+// https://tetris.wiki/ARS
+pub struct Ars;
+
+impl RotationSystem for Ars {
+    fn block(&self, ttype: PieceType, state: usize) -> &'static Block {
+        match ttype {
+            PieceType::I => &ARS_I[state],
+            PieceType::O => &ARS_O,
+            PieceType::T => &ARS_T[state],
+            PieceType::S => &ARS_S[state],
+            PieceType::Z => &ARS_Z[state],
+            PieceType::J => &ARS_J[state],
+            PieceType::L => &ARS_L[state],
+        }
+    }
+
+    // the top row of every spawn state is empty, so all of them start one row up
+    fn spawn_position(&self, _ttype: PieceType, width: usize) -> (isize, isize) {
+        ((width as isize - 4) / 2, -1)
+    }
+
+    fn kicks(&self, ttype: PieceType, _from: usize, _to: usize) -> &'static [Kick] {
+        match ttype {
+            PieceType::I | PieceType::O => &KICKS_NONE,
+            _ => &KICKS,
+        }
+    }
+
+    // the center column rule, J, L and T can't kick when the first blocked square
+    // found in reading order is in the middle column of their 3x3 box
+    fn can_kick(&self, ttype: PieceType, to: usize, blocked: &dyn Fn(usize, usize) -> bool) -> bool {
+        if !matches!(ttype, PieceType::J | PieceType::L | PieceType::T) {
+            return true;
+        }
+
+        let block = self.block(ttype, to);
+        for y in 0..3 {
+            for x in 0..3 {
+                if has_square(block, x, y) && blocked(x, y) {
+                    return x != 1;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+const KICKS_NONE: [Kick; 1] = [(0, 0)];
+
+const KICKS: [Kick; 3] = [(0, 0), (1, 0), (-1, 0)];
+
+const ARS_I: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 1,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 1,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+    ]),
+];
+
+const ARS_O: Block = block([
+    0, 0, 0, 0,
+    0, 1, 1, 0,
+    0, 1, 1, 0,
+    0, 0, 0, 0,
+]);
+
+const ARS_T: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        0, 1, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const ARS_S: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        0, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        1, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        0, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        1, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const ARS_Z: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const ARS_J: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const ARS_L: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        1, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        0, 0, 1, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+];
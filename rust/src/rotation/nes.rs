@@ -0,0 +1,205 @@
+use crate::rotation::{Kick, RotationSystem};
+use crate::tetrimino::{block, Block, PieceType};
+
+// Nintendo Rotation System from the NES version, right handed and without any kicks
+// https://tetris.wiki/Nintendo_Rotation_System
+pub struct Nes;
+
+impl RotationSystem for Nes {
+    fn block(&self, ttype: PieceType, state: usize) -> &'static Block {
+        match ttype {
+            PieceType::I => &NES_I[state],
+            PieceType::O => &NES_O,
+            PieceType::T => &NES_T[state],
+            PieceType::S => &NES_S[state],
+            PieceType::Z => &NES_Z[state],
+            PieceType::J => &NES_J[state],
+            PieceType::L => &NES_L[state],
+        }
+    }
+
+    // three wide pieces are centered one column further right than the guideline
+    fn spawn_position(&self, ttype: PieceType, width: usize) -> (isize, isize) {
+        let x = (width as isize - 4) / 2;
+        match ttype {
+            PieceType::I => (x, -2),
+            PieceType::O => (x, -1),
+            _ => (x + 1, -1),
+        }
+    }
+
+    fn kicks(&self, _ttype: PieceType, _from: usize, _to: usize) -> &'static [Kick] {
+        &KICKS_NONE
+    }
+}
+
+const KICKS_NONE: [Kick; 1] = [(0, 0)];
+
+const NES_I: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+        1, 1, 1, 1,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+        1, 1, 1, 1,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+    ]),
+];
+
+const NES_O: Block = block([
+    0, 0, 0, 0,
+    0, 1, 1, 0,
+    0, 1, 1, 0,
+    0, 0, 0, 0,
+]);
+
+const NES_T: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const NES_S: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        0, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        0, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const NES_Z: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const NES_J: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        1, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const NES_L: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        1, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+];
@@ -0,0 +1,245 @@
+use crate::rotation::{Kick, RotationSystem};
+use crate::tetrimino::{block, Block, PieceType};
+
+// Super Rotation System used by the guideline games
+// https://tetris.fandom.com/wiki/SRS
+pub struct Srs;
+
+impl RotationSystem for Srs {
+    fn block(&self, ttype: PieceType, state: usize) -> &'static Block {
+        match ttype {
+            PieceType::I => &SRS_I[state],
+            PieceType::O => &SRS_O,
+            PieceType::T => &SRS_T[state],
+            PieceType::S => &SRS_S[state],
+            PieceType::Z => &SRS_Z[state],
+            PieceType::J => &SRS_J[state],
+            PieceType::L => &SRS_L[state],
+        }
+    }
+
+    // every piece enters flat side down on the top row, centered and rounded to the left
+    fn spawn_position(&self, ttype: PieceType, width: usize) -> (isize, isize) {
+        let x = (width as isize - 4) / 2;
+        match ttype {
+            PieceType::I => (x, -1),
+            _ => (x, 0),
+        }
+    }
+
+    fn kicks(&self, ttype: PieceType, from: usize, to: usize) -> &'static [Kick] {
+        let transition = match (from, to) {
+            (0, 1) => 0,
+            (1, 0) => 1,
+            (1, 2) => 2,
+            (2, 1) => 3,
+            (2, 3) => 4,
+            (3, 2) => 5,
+            (3, 0) => 6,
+            (0, 3) => 7,
+            _ => return &KICKS_NONE,
+        };
+
+        match ttype {
+            PieceType::I => &KICKS_I[transition],
+            PieceType::O => &KICKS_NONE,
+            _ => &KICKS_JLSTZ[transition],
+        }
+    }
+}
+
+const KICKS_NONE: [Kick; 1] = [(0, 0)];
+
+// wall kicks indexed by the transitions 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L
+// https://tetris.fandom.com/wiki/SRS#Wall_Kicks
+// y is flipped from the wiki's tables because the board's y axis points down
+const KICKS_JLSTZ: [[Kick; 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+];
+
+const KICKS_I: [[Kick; 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)],
+    [(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)],
+    [(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)],
+    [(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)],
+    [(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)],
+];
+
+const SRS_I: [Block; 4] = [
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 1,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+        1, 1, 1, 1,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+    ]),
+];
+
+const SRS_O: Block = block([
+    0, 1, 1, 0,
+    0, 1, 1, 0,
+    0, 0, 0, 0,
+    0, 0, 0, 0,
+]);
+
+const SRS_T: [Block; 4] = [
+    block([
+        0, 1, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const SRS_S: [Block; 4] = [
+    block([
+        0, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        0, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        1, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const SRS_Z: [Block; 4] = [
+    block([
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 1, 0,
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        1, 1, 0, 0,
+        1, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const SRS_J: [Block; 4] = [
+    block([
+        1, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
+
+const SRS_L: [Block; 4] = [
+    block([
+        0, 0, 1, 0,
+        1, 1, 1, 0,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        0, 0, 0, 0,
+        1, 1, 1, 0,
+        1, 0, 0, 0,
+        0, 0, 0, 0,
+    ]),
+    block([
+        1, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+    ]),
+];
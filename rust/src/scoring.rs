@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::LineClear;
+use crate::tspin::TSpin;
+
+// A scoring rule turns what happened when a piece locked into points.
+// Combos, back to back and perfect clears are tracked by the game and handed over
+// through LineClear, so rules themselves don't hold any state.
+pub trait ScoringRule {
+    fn line_clear(&self, clear: &LineClear, level: usize) -> usize;
+
+    // points for rows the player moved the piece down, gravity never scores
+    fn soft_drop(&self, rows: usize) -> usize;
+    fn hard_drop(&self, rows: usize) -> usize;
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoringRuleKind {
+    Guideline,
+    Nes,
+    Tgm,
+}
+
+impl ScoringRuleKind {
+    pub fn build(self) -> Box<dyn ScoringRule> {
+        match self {
+            ScoringRuleKind::Guideline => Box::new(GuidelineScoring),
+            ScoringRuleKind::Nes => Box::new(NesScoring),
+            ScoringRuleKind::Tgm => Box::new(TgmScoring),
+        }
+    }
+}
+
+// Scoring from the 2009 guideline with the perfect clear bonuses added later on
+// https://tetris.wiki/Scoring#Recent_guideline_compatible_games
+pub struct GuidelineScoring;
+
+impl ScoringRule for GuidelineScoring {
+    fn line_clear(&self, clear: &LineClear, level: usize) -> usize {
+        let base = match (clear.tspin, clear.lines) {
+            (TSpin::None, 0) => 0,
+            (TSpin::None, 1) => 100,
+            (TSpin::None, 2) => 300,
+            (TSpin::None, 3) => 500,
+            (TSpin::None, _) => 800,
+            (TSpin::Mini, 0) => 100,
+            (TSpin::Mini, 1) => 200,
+            (TSpin::Mini, _) => 400,
+            (TSpin::Full, 0) => 400,
+            (TSpin::Full, 1) => 800,
+            (TSpin::Full, 2) => 1200,
+            (TSpin::Full, _) => 1600,
+        };
+
+        // difficult clears in a row are worth half as much again
+        let base = if clear.back_to_back { base * 3 / 2 } else { base };
+
+        let combo = 50 * clear.combo;
+
+        let perfect_clear = match (clear.perfect_clear, clear.lines) {
+            (false, _) | (true, 0) => 0,
+            (true, 1) => 800,
+            (true, 2) => 1200,
+            (true, 3) => 1800,
+            (true, _) if clear.back_to_back => 3200,
+            (true, _) => 2000,
+        };
+
+        (base + combo + perfect_clear) * level
+    }
+
+    fn soft_drop(&self, rows: usize) -> usize {
+        rows
+    }
+
+    fn hard_drop(&self, rows: usize) -> usize {
+        rows * 2
+    }
+}
+
+// Scoring from the NES version, there are no spins, combos or hard drops there.
+// The NES counts levels from 0, so level 1 here is its level 0.
+// https://tetris.wiki/Scoring#Original_Nintendo_scoring_system
+pub struct NesScoring;
+
+impl ScoringRule for NesScoring {
+    fn line_clear(&self, clear: &LineClear, level: usize) -> usize {
+        let base = match clear.lines {
+            0 => 0,
+            1 => 40,
+            2 => 100,
+            3 => 300,
+            _ => 1200,
+        };
+
+        base * level
+    }
+
+    fn soft_drop(&self, rows: usize) -> usize {
+        rows
+    }
+
+    fn hard_drop(&self, _rows: usize) -> usize {
+        0
+    }
+}
+
+// Scoring from TGM, where the level counts from 0 to 999 and clears are worth more the higher it is.
+// TGM adds the rows dropped to the clear before it multiplies, here they're scored on their own,
+// and its combo multiplier is simplified to one more for every clear in a row.
+// https://tetris.wiki/Tetris_The_Grand_Master#Scoring
+pub struct TgmScoring;
+
+impl ScoringRule for TgmScoring {
+    fn line_clear(&self, clear: &LineClear, level: usize) -> usize {
+        if clear.lines == 0 {
+            return 0;
+        }
+
+        let bravo = if clear.perfect_clear { 4 } else { 1 };
+        (level + clear.lines).div_ceil(4) * clear.lines * (clear.combo + 1) * bravo
+    }
+
+    fn soft_drop(&self, rows: usize) -> usize {
+        rows
+    }
+
+    fn hard_drop(&self, rows: usize) -> usize {
+        rows
+    }
+}
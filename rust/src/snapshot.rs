@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::error::{self, Error};
+use crate::history::History;
+use crate::input::AutoShift;
+use crate::randomizer::{Preset, RandomizerState};
+use crate::replay::Frame;
+use crate::rng::Rng;
+use crate::tetrimino::PieceType;
+use crate::topout::TopOut;
+use crate::{ActivePiece, Game, Input, LineClear, Rules, State};
+
+// bumped whenever the format changes in a way older snapshots can't be read with
+pub const SNAPSHOT_VERSION: u32 = 7;
+
+// Everything a game is in the middle of, so it can be saved and picked up again exactly where it was.
+// The rotation system and scoring rule are built again from the rules, and the randomizer from
+// its kind in the rules, so games dealing from a custom randomizer can't be restored.
+// Placements to undo aren't saved, a restored game starts its history over.
+// The replay can be left out, it grows with every frame of the game.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    version: u32,
+    width: usize,
+    height: usize,
+    buffer_rows: usize,
+    board: Board,
+    rules: Rules,
+    seed: u64,
+    rng: Rng,
+    generator: RandomizerState,
+    next_pieces: Vec<PieceType>,
+    active_piece: ActivePiece,
+    held_type: Option<PieceType>,
+    can_hold: bool,
+    last_rotation: Option<usize>,
+    last_clear: LineClear,
+    combo: Option<usize>,
+    back_to_back: bool,
+    state: State,
+    top_out: TopOut,
+    elapsed: Duration,
+    fall_rate: Duration,
+    entry_delay: Option<Duration>,
+    lock_elapsed: Duration,
+    lock_delay: Duration,
+    lock_resets: usize,
+    lowest_row: isize,
+    lines: usize,
+    goal_remaining: usize,
+    game_time: Duration,
+    splits: Vec<Duration>,
+    garbage_lines: usize,
+    garbage_hole: Option<usize>,
+    garbage_timer: Duration,
+    garbage_interval: Duration,
+    sections: Vec<Duration>,
+    gm_qualified: bool,
+    inputs: Vec<Input>,
+    #[serde(default)]
+    frames: Option<Vec<Frame>>,
+    auto_shift: AutoShift,
+    soft_drop: bool,
+    score: usize,
+    level: usize,
+}
+
+// read before the rest so a snapshot from another version gets a clear error
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+impl Snapshot {
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("snapshots only hold plain data")
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("snapshots only hold plain data")
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Snapshot, Error> {
+        let version: Version = bincode::deserialize(bytes).map_err(invalid)?;
+        check_version(version.version)?;
+
+        bincode::deserialize(bytes).map_err(invalid)
+    }
+
+    pub(crate) fn from_json(json: &str) -> Result<Snapshot, Error> {
+        let version: Version = serde_json::from_str(json).map_err(invalid)?;
+        check_version(version.version)?;
+
+        serde_json::from_str(json).map_err(invalid)
+    }
+}
+
+fn invalid(e: impl ToString) -> Error {
+    Error::InvalidSnapshot(e.to_string())
+}
+
+fn check_version(version: u32) -> Result<(), Error> {
+    if version != SNAPSHOT_VERSION {
+        return Err(invalid(format!("unsupported snapshot version {}", version)));
+    }
+
+    Ok(())
+}
+
+impl Game {
+    pub(crate) fn snapshot(&self, with_replay: bool) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            width: self.width,
+            height: self.height,
+            buffer_rows: self.buffer_rows,
+            board: self.board.clone(),
+            rules: self.rules,
+            seed: self.seed,
+            rng: self.rng,
+            generator: self.generator.save(),
+            next_pieces: self.next_pieces.clone(),
+            active_piece: self.active_piece,
+            held_type: self.held_type,
+            can_hold: self.can_hold,
+            last_rotation: self.last_rotation,
+            last_clear: self.last_clear,
+            combo: self.combo,
+            back_to_back: self.back_to_back,
+            state: self.state,
+            top_out: self.top_out,
+            elapsed: self.elapsed,
+            fall_rate: self.fall_rate,
+            entry_delay: self.entry_delay,
+            lock_elapsed: self.lock_elapsed,
+            lock_delay: self.lock_delay,
+            lock_resets: self.lock_resets,
+            lowest_row: self.lowest_row,
+            lines: self.lines,
+            goal_remaining: self.goal_remaining,
+            game_time: self.game_time,
+            splits: self.splits.clone(),
+            garbage_lines: self.garbage_lines,
+            garbage_hole: self.garbage_hole,
+            garbage_timer: self.garbage_timer,
+            garbage_interval: self.garbage_interval,
+            sections: self.sections.clone(),
+            gm_qualified: self.gm_qualified,
+            inputs: self.inputs.clone(),
+            frames: if with_replay { self.frames.clone() } else { None },
+            auto_shift: self.auto_shift.clone(),
+            soft_drop: self.soft_drop,
+            score: self.score,
+            level: self.level,
+        }
+    }
+
+    pub(crate) fn from_snapshot(snapshot: Snapshot) -> Result<Game, Error> {
+        let visible = snapshot.height.checked_sub(snapshot.buffer_rows)
+            .ok_or_else(|| invalid("more buffer rows than rows"))?;
+        error::check_dimensions(snapshot.width, visible, snapshot.buffer_rows)?;
+        error::check_start_level(snapshot.rules.start_level)?;
+        error::check_goal(&snapshot.rules)?;
+
+        if !snapshot.board.has_size(snapshot.width, snapshot.height) {
+            return Err(invalid("board doesn't match the game's size"));
+        }
+        if snapshot.garbage_lines > snapshot.height {
+            return Err(invalid("more garbage than rows"));
+        }
+        if snapshot.next_pieces.len() != 3 {
+            return Err(invalid("queue doesn't have three pieces"));
+        }
+        if snapshot.active_piece.piece.state >= 4 {
+            return Err(invalid("active piece isn't in one of the four rotation states"));
+        }
+        if snapshot.garbage_hole.is_some_and(|hole| hole >= snapshot.width) {
+            return Err(invalid("garbage hole is off the board"));
+        }
+
+        let mut generator = snapshot.rules.randomizer.build();
+        if let RandomizerState::Preset { .. } = snapshot.generator {
+            generator = Box::new(Preset::new(Vec::new(), generator));
+        }
+        if !generator.load(&snapshot.generator) {
+            return Err(invalid("randomizer state doesn't match the rules"));
+        }
+
+        let mut game = Game {
+            width: snapshot.width,
+            height: snapshot.height,
+            buffer_rows: snapshot.buffer_rows,
+            board: snapshot.board,
+            rules: snapshot.rules,
+            rotation_system: snapshot.rules.rotation_system.build(),
+            scoring_rule: snapshot.rules.scoring_rule.build(),
+            seed: snapshot.seed,
+            rng: snapshot.rng,
+            generator,
+            next_pieces: snapshot.next_pieces,
+            active_piece: snapshot.active_piece,
+            held_type: snapshot.held_type,
+            can_hold: snapshot.can_hold,
+            last_rotation: snapshot.last_rotation,
+            last_clear: snapshot.last_clear,
+            combo: snapshot.combo,
+            back_to_back: snapshot.back_to_back,
+            state: snapshot.state,
+            top_out: snapshot.top_out,
+            elapsed: snapshot.elapsed,
+            fall_rate: snapshot.fall_rate,
+            entry_delay: snapshot.entry_delay,
+            lock_elapsed: snapshot.lock_elapsed,
+            lock_delay: snapshot.lock_delay,
+            lock_resets: snapshot.lock_resets,
+            lowest_row: snapshot.lowest_row,
+            lines: snapshot.lines,
+            goal_remaining: snapshot.goal_remaining,
+            game_time: snapshot.game_time,
+            splits: snapshot.splits,
+            garbage_lines: snapshot.garbage_lines,
+            garbage_hole: snapshot.garbage_hole,
+            garbage_timer: snapshot.garbage_timer,
+            garbage_interval: snapshot.garbage_interval,
+            sections: snapshot.sections,
+            gm_qualified: snapshot.gm_qualified && snapshot.rules.start_level == 0,
+            active_piece_indexes: Vec::new(),
+            ground_hint_indexes: Vec::new(),
+            inputs: snapshot.inputs,
+            frames: snapshot.frames,
+            // positions from fumen deal their pieces before the randomizer's
+            replayable: !matches!(snapshot.generator, RandomizerState::Preset { .. }),
+            history: History::new(),
+            auto_shift: snapshot.auto_shift,
+            soft_drop: snapshot.soft_drop,
+            score: snapshot.score,
+            level: snapshot.level,
+        };
+        game.update_active_piece_coords();
+        game.update_ground_hint_coords();
+        game.save_placement();
+
+        Ok(game)
+    }
+}
@@ -1,179 +1,29 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-pub type Block = [u8; 16];
+// a piece in its 4x4 box as one bit mask per row, bit x is set when column x has a square
+pub type Block = [u8; 4];
 
-const BLOCK_I: [Block; 4] = [
-    [
-        0, 0, 1, 0,
-        0, 0, 1, 0,
-        0, 0, 1, 0,
-        0, 0, 1, 0,
-    ],
-    [
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-        1, 1, 1, 1,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 1, 0, 0,
-    ],
-    [
-        0, 0, 0, 0,
-        1, 1, 1, 1,
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-    ],
-];
-
-const BLOCK_O: Block = [
-    0, 1, 1, 0,
-    0, 1, 1, 0,
-    0, 0, 0, 0,
-    0, 0, 0, 0,
-];
-
-const BLOCK_T: [Block; 4] = [
-    [
-        0, 0, 0, 0,
-        1, 1, 1, 0,
-        0, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 1, 0, 0,
-        1, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 1, 0, 0,
-        1, 1, 1, 0,
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 1, 0, 0,
-        0, 1, 1, 0,
-        0, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-];
-
-const BLOCK_S: [Block; 4] = [
-    [
-        0, 1, 1, 0,
-        1, 1, 0, 0,
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 1, 0, 0,
-        0, 1, 1, 0,
-        0, 0, 1, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 0, 0, 0,
-        0, 1, 1, 0,
-        1, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        1, 0, 0, 0,
-        1, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 0, 0, 0,
-    ]
-];
-
-const BLOCK_Z: [Block; 4] = [
-    [
-        1, 1, 0, 0,
-        0, 1, 1, 0,
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 0, 1, 0,
-        0, 1, 1, 0,
-        0, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 0, 0, 0,
-        1, 1, 0, 0,
-        0, 1, 1, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 1, 0, 0,
-        1, 1, 0, 0,
-        1, 0, 0, 0,
-        0, 0, 0, 0,
-    ]
-];
+// build a block from a 4x4 grid of 0s and 1s written row by row, so the piece tables stay readable
+pub const fn block(grid: [u8; 16]) -> Block {
+    let mut rows = [0; 4];
+    let mut i = 0;
+    while i < 16 {
+        rows[i / 4] |= grid[i] << (i % 4);
+        i += 1;
+    }
 
-const BLOCK_J: [Block; 4] = [
-    [
-        0, 1, 0, 0,
-        0, 1, 0, 0,
-        1, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        1, 0, 0, 0,
-        1, 1, 1, 0,
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 1, 1, 0,
-        0, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 0, 0, 0,
-        1, 1, 1, 0,
-        0, 0, 1, 0,
-        0, 0, 0, 0,
-    ],
-];
+    rows
+}
 
-const BLOCK_L: [Block; 4] = [
-    [
-        0, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 1, 1, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        1, 1, 1, 0,
-        1, 0, 0, 0,
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        1, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 1, 0, 0,
-        0, 0, 0, 0,
-    ],
-    [
-        0, 0, 1, 0,
-        1, 1, 1, 0,
-        0, 0, 0, 0,
-        0, 0, 0, 0,
-    ],
-];
+pub fn has_square(block: &Block, x: usize, y: usize) -> bool {
+    block[y] >> x & 1 == 1
+}
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Color {
     None = 0,
     Cyan = 1,
@@ -182,12 +32,14 @@ pub enum Color {
     Green = 4,
     Red = 5,
     Blue = 6,
-    Orange = 7
+    Orange = 7,
+    // garbage rows pushed up from the bottom
+    Gray = 8
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PieceType {
     I = 1,
     O = 2,
@@ -198,15 +50,28 @@ pub enum PieceType {
     L = 7
 }
 
+impl PieceType {
+    pub const ALL: [PieceType; 7] = [
+        PieceType::I,
+        PieceType::O,
+        PieceType::T,
+        PieceType::S,
+        PieceType::Z,
+        PieceType::J,
+        PieceType::L,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tetrimino {
     pub ttype: PieceType,
-    state: usize,
+    pub state: usize,
 }
 
 impl Tetrimino {
 
-    pub fn from(ttype: PieceType) -> Self {
-        Tetrimino { ttype, state: 0 }
+    pub fn from(ttype: PieceType, state: usize) -> Self {
+        Tetrimino { ttype, state }
     }
 
     pub fn color(&self) -> Color {
@@ -225,28 +90,13 @@ impl Tetrimino {
         }
     }
 
-    pub fn block(&self) -> &'static Block {
-        Self::block_of(self.ttype, self.state)
-    }
-
-    pub fn block_of(ttype: PieceType, state: usize) -> &'static Block {
-        match ttype {
-            PieceType::I => BLOCK_I.get(state).unwrap(),
-            PieceType::O => &BLOCK_O,
-            PieceType::T => BLOCK_T.get(state).unwrap(),
-            PieceType::S => BLOCK_S.get(state).unwrap(),
-            PieceType::Z => BLOCK_Z.get(state).unwrap(),
-            PieceType::J => BLOCK_J.get(state).unwrap(),
-            PieceType::L => BLOCK_L.get(state).unwrap(),
-        }
-    }
-
+    // rotation states are numbered clockwise, see RotationSystem
     pub fn rotate_clockwise(&self) -> Tetrimino {
         Tetrimino {
             ttype: self.ttype,
             state: match self.state {
-                0 => 3,
-                _ => self.state - 1
+                3 => 0,
+                _ => self.state + 1
             },
         }
     }
@@ -255,8 +105,8 @@ impl Tetrimino {
         Tetrimino {
             ttype: self.ttype,
             state: match self.state {
-                3 => 0,
-                _ => self.state + 1
+                0 => 3,
+                _ => self.state - 1
             },
         }
     }
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// why a game ended, None while it's still going
+// https://tetris.wiki/Top_out
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TopOut {
+    None,
+    // a new piece spawned overlapping the stack
+    BlockOut,
+    // a piece locked entirely above the visible field
+    LockOut,
+    // garbage coming in from the bottom pushed blocks past the top of the board
+    GarbageOut,
+}
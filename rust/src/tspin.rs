@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::tetrimino::{has_square, Block};
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TSpin {
+    None,
+    Mini,
+    Full,
+}
+
+// the kick that lets a T twist into a triple slot, a spin using it always counts as full
+// https://tetris.wiki/T-Spin#Current_rules
+const UPGRADE_KICK: usize = 4;
+
+// Three corner check for a T piece that just locked after a rotation.
+// `kick` is the index of the wall kick the last rotation used and `occupied` tells
+// whether the square at x, y of the piece's 4x4 box is taken or out of bounds.
+// The T's center and the side it points to are worked out from the block itself,
+// so this works with the pieces of any rotation system.
+pub fn detect(block: &Block, kick: usize, occupied: &dyn Fn(isize, isize) -> bool) -> TSpin {
+    let filled = |x: isize, y: isize| {
+        (0..4).contains(&x) && (0..4).contains(&y) && has_square(block, x as usize, y as usize)
+    };
+
+    // the center is the only square of a T with three neighbours,
+    // the side without a neighbour is the flat back of the piece
+    let mut center = None;
+    for y in 0..4 {
+        for x in 0..4 {
+            let neighbours = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .filter(|(dx, dy)| filled(x + dx, y + dy))
+                .count();
+
+            if filled(x, y) && neighbours == 3 {
+                center = Some((x, y));
+            }
+        }
+    }
+
+    let (center_x, center_y) = match center {
+        Some(center) => center,
+        None => return TSpin::None,
+    };
+
+    let (back_x, back_y) = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+        .iter()
+        .copied()
+        .find(|(dx, dy)| !filled(center_x + dx, center_y + dy))
+        .unwrap();
+
+    let corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+    let taken = corners
+        .iter()
+        .filter(|(dx, dy)| occupied(center_x + dx, center_y + dy))
+        .count();
+
+    if taken < 3 {
+        return TSpin::None;
+    }
+
+    // the front corners are the two on the side the T is pointing to
+    let front = corners
+        .iter()
+        .filter(|(dx, dy)| *dx == -back_x || *dy == -back_y)
+        .filter(|(dx, dy)| occupied(center_x + dx, center_y + dy))
+        .count();
+
+    if front == 2 || kick == UPGRADE_KICK {
+        TSpin::Full
+    } else {
+        TSpin::Mini
+    }
+}
@@ -1,15 +1,38 @@
-use js_sys::Math;
+use crate::rng::Rng;
 
-// Shuffle given array using javascript's random
-pub fn shuffle<T: Copy>(arr: &mut [T]) {
+// Shuffle given array with the game's random number generator
+pub fn shuffle<T: Copy>(arr: &mut [T], rng: &mut Rng) {
     let mut current_index = arr.len();
 
     while current_index != 0 {
-        let random_index = (Math::random() * current_index as f64).floor() as usize;
+        let random_index = rng.below(current_index);
         current_index -= 1;
 
-        let temp = arr[current_index];
-        arr[current_index] = arr[random_index];
-        arr[random_index] = temp;
+        arr.swap(current_index, random_index);
     }
-}
\ No newline at end of file
+}
+
+// Seed for games that aren't given one, using javascript's random
+#[cfg(feature = "wasm")]
+pub fn random_seed() -> u64 {
+    use js_sys::Math;
+
+    let high = (Math::random() * u32::MAX as f64) as u64;
+    let low = (Math::random() * u32::MAX as f64) as u64;
+
+    (high << 32) | low
+}
+
+// outside of the browser the seed comes from the clock mixed with std's per process random keys
+#[cfg(not(feature = "wasm"))]
+pub fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(now.as_nanos());
+
+    hasher.finish()
+}
@@ -1,35 +1,147 @@
-use wasm_bindgen_test::{wasm_bindgen_test_configure, wasm_bindgen_test};
-use futures::prelude::*;
-use wasm_bindgen::JsValue;
-use wasm_bindgen_futures::JsFuture;
+mod common;
 
-wasm_bindgen_test_configure!(run_in_browser);
+use common::hard_drop;
+use tetris_rs::{Error, Game, PieceType, Rules, TopOut};
 
+// hard drop every piece and collect the types that became active
+fn drop_pieces(game: &mut Game, count: usize) -> Vec<PieceType> {
+    let mut pieces = Vec::new();
+
+    for _ in 0..count {
+        pieces.push(game.active_piece_type());
+        hard_drop(game, 1000);
+    }
+
+    pieces
+}
+
+#[test]
+fn same_seed_deals_same_pieces() {
+    let mut a = Game::new_with_seed(10, 20, 42).unwrap();
+    let mut b = Game::new_with_seed(10, 20, 42).unwrap();
+
+    assert_eq!(a.seed(), 42);
+    assert_eq!(a.queue(), b.queue());
+    assert_eq!(drop_pieces(&mut a, 5), drop_pieces(&mut b, 5));
+    assert_eq!(a.queue(), b.queue());
+}
+
+#[test]
+fn restart_with_seed_deals_same_pieces() {
+    let mut game = Game::new_with_seed(10, 20, 7).unwrap();
+    let first = drop_pieces(&mut game, 5);
+
+    game.restart_with_seed(7);
+    assert_eq!(drop_pieces(&mut game, 5), first);
+}
+
+#[test]
+fn every_bag_has_all_seven_pieces() {
+    let mut game = Game::new_with_seed(10, 40, 1234).unwrap();
+    let mut pieces = drop_pieces(&mut game, 7);
+    pieces.sort_by_key(|t| *t as u8);
+
+    use PieceType::*;
+    assert_eq!(pieces, vec![I, O, T, S, Z, J, L]);
+}
 
-// This runs a unit test in native Rust, so it can only use Rust APIs.
 #[test]
-fn rust_test() {
-    assert_eq!(1, 1);
+fn pieces_spawn_above_the_visible_field() {
+    let game = Game::new_with_seed(10, 20, 1).unwrap();
+
+    assert_eq!(game.buffer_rows(), 20);
+    assert_eq!(game.visible_board().len(), 200);
+
+    // at most the bottom row of the new piece peeks into the visible field
+    assert!(game.active_piece_coords_len() < 4);
 }
 
+// drop pieces in the middle until the stack reaches the top
+fn top_out(rules: Rules) -> TopOut {
+    let mut game = Game::with_rules(10, 20, rules, 3).unwrap();
+    drop_pieces(&mut game, 200);
 
-// This runs a unit test in the browser, so it can use browser APIs.
-#[wasm_bindgen_test]
-fn web_test() {
-    assert_eq!(1, 1);
+    assert!(game.is_lost());
+    game.top_out()
+}
+
+#[test]
+fn stacking_to_the_top_locks_out() {
+    assert_eq!(top_out(Rules::default()), TopOut::LockOut);
 }
 
+#[test]
+fn stacking_to_the_top_without_lock_out_blocks_out() {
+    let rules = Rules { lock_out: false, ..Rules::default() };
+    assert_eq!(top_out(rules), TopOut::BlockOut);
+}
+
+#[test]
+fn garbage_pushing_blocks_off_the_board_garbage_outs() {
+    let mut game = Game::new_with_seed(10, 20, 5).unwrap();
+
+    // the board holds 40 rows with the hidden ones, the next row pushes the top one off
+    for _ in 0..40 {
+        game.add_garbage_row(0);
+    }
+    assert!(!game.is_lost());
+
+    game.add_garbage_row(0);
+    assert!(game.is_lost());
+    assert_eq!(game.top_out(), TopOut::GarbageOut);
+}
+
+#[test]
+fn board_dimensions_are_checked() {
+    assert_eq!(Game::new_with_seed(3, 20, 1).err(), Some(Error::InvalidWidth(3)));
+    assert_eq!(Game::new_with_seed(10, 2, 1).err(), Some(Error::InvalidHeight(2, 20)));
+    assert_eq!(Game::new_with_seed(10, 1020, 1).err(), Some(Error::InvalidHeight(1020, 20)));
+    assert!(Game::new_with_seed(4, 4, 1).is_ok());
+}
+
+#[test]
+fn large_boards_export_every_coordinate() {
+    let mut game = Game::new_with_seed(40, 40, 1).unwrap();
+    game.update(0);
+    let bottom = (38 * 40) as u32;
+
+    // the ground hint rests on the floor, far past the first 255 squares
+    let hint = game.ground_hint_indexes();
+    assert_eq!(hint.len(), 4);
+    assert!(hint.iter().all(|idx| *idx >= bottom));
+}
+
+// the browser tests need the wasm build, run them with `wasm-pack test --headless --features wasm`
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+mod web {
+    use wasm_bindgen_test::{wasm_bindgen_test_configure, wasm_bindgen_test};
+    use futures::prelude::*;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_futures::JsFuture;
+
+    use tetris_rs::Game;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    // This runs a unit test in the browser, so it can use browser APIs.
+    #[wasm_bindgen_test]
+    fn web_test() {
+        let game = Game::new(10, 20).unwrap();
+        assert!(!game.is_lost());
+    }
+
 
-// This runs a unit test in the browser, and in addition it supports asynchronous Future APIs.
-#[wasm_bindgen_test(async)]
-fn async_test() -> impl Future<Item = (), Error = JsValue> {
-    // Creates a JavaScript Promise which will asynchronously resolve with the value 42.
-    let promise = js_sys::Promise::resolve(&JsValue::from(42));
+    // This runs a unit test in the browser, and in addition it supports asynchronous Future APIs.
+    #[wasm_bindgen_test(async)]
+    fn async_test() -> impl Future<Item = (), Error = JsValue> {
+        // Creates a JavaScript Promise which will asynchronously resolve with the value 42.
+        let promise = js_sys::Promise::resolve(&JsValue::from(42));
 
-    // Converts that Promise into a Future.
-    // The unit test will wait for the Future to resolve.
-    JsFuture::from(promise)
-        .map(|x| {
-            assert_eq!(x, 42);
-        })
+        // Converts that Promise into a Future.
+        // The unit test will wait for the Future to resolve.
+        JsFuture::from(promise)
+            .map(|x| {
+                assert_eq!(x, 42);
+            })
+    }
 }
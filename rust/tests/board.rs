@@ -0,0 +1,51 @@
+use tetris_rs::{block, Block, Board, Color};
+
+const O: Block = block([
+    1, 1, 0, 0,
+    1, 1, 0, 0,
+    0, 0, 0, 0,
+    0, 0, 0, 0,
+]);
+
+#[test]
+fn blocks_collide_with_walls_floor_and_stack() {
+    let mut board = Board::new(10, 4);
+
+    assert!(!board.collides(&O, 0, 0));
+    assert!(!board.collides(&O, 8, 2));
+    assert!(board.collides(&O, -1, 0));
+    assert!(board.collides(&O, 9, 0));
+    assert!(board.collides(&O, 0, 3));
+
+    board.place(&O, 4, 2, Color::Yellow);
+    assert_eq!(board.rows(), &[0, 0, 0b110000, 0b110000]);
+    assert!(board.collides(&O, 5, 1));
+    assert!(!board.collides(&O, 4, 0));
+    assert!(board.overlaps(&O, 3, 1));
+}
+
+#[test]
+fn full_rows_are_cleared_and_the_rest_moves_down() {
+    let mut board = Board::new(4, 4);
+
+    board.place(&O, 0, 2, Color::Yellow);
+    board.place(&O, 2, 2, Color::Yellow);
+    board.place(&O, 0, 0, Color::Red);
+    assert_eq!(board.clear_lines(), 2);
+
+    assert_eq!(board.rows(), &[0, 0, 0b11, 0b11]);
+    assert_eq!(board.colors()[12], Color::Red);
+    assert_eq!(board.colors()[15], Color::None);
+    assert_eq!(board.clear_lines(), 0);
+}
+
+#[test]
+fn garbage_pushes_the_stack_up() {
+    let mut board = Board::new(64, 4);
+    board.place(&O, 62, 1, Color::Blue);
+
+    assert!(!board.push_garbage(3));
+    assert_eq!(board.rows()[3], board.full_row() & !(1 << 3));
+    assert!(board.push_garbage(3));
+    assert!(!board.is_empty());
+}
@@ -0,0 +1,122 @@
+// helpers shared by the test files, not every file uses all of them
+#![allow(dead_code)]
+
+use tetris_rs::fumen::{self, Field, Operation, Page};
+use tetris_rs::{Action, Color, Game, PieceType, Rng, Rules};
+
+const ACTIONS: [Action; 7] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::SoftDrop,
+    Action::HardDrop,
+    Action::RotateClockwise,
+    Action::RotateCounterClockwise,
+    Action::Hold,
+];
+
+// press and let go of a key at the start of the next frame
+pub fn tap(game: &mut Game, action: Action) {
+    game.press(action, 0);
+    game.release(action, 0);
+}
+
+// hard drop the active piece in a frame `elapsed` microseconds long
+pub fn hard_drop(game: &mut Game, elapsed: u64) {
+    tap(game, Action::HardDrop);
+    game.update(elapsed);
+}
+
+// a ten wide game on the stack drawn from the top down, '#' for a block, with the
+// pieces from a quiz comment like "#Q=[](T)IOS"
+pub fn position(stack: &[&str], quiz: &str, rules: Rules) -> Game {
+    let mut page = Page::new(field(stack));
+    page.comment = quiz.to_string();
+    Game::from_fumen(&fumen::encode(&[page]), 0, rules, 1).unwrap()
+}
+
+// like `position` with the active piece already somewhere on the field
+pub fn placed(stack: &[&str], piece: Operation, rules: Rules) -> Game {
+    let mut page = Page::new(field(stack));
+    page.operation = Some(piece);
+    Game::from_fumen(&fumen::encode(&[page]), 0, rules, 1).unwrap()
+}
+
+fn field(stack: &[&str]) -> Field {
+    let mut field = Field::new();
+    for (y, row) in stack.iter().rev().enumerate() {
+        for (x, square) in row.chars().enumerate() {
+            if square == '#' {
+                field.set(x, y as isize, Color::Gray);
+            }
+        }
+    }
+
+    field
+}
+
+// a well in the last column `rows` deep with I pieces to fill it
+pub fn well(rows: usize, rules: Rules) -> Game {
+    position(&vec!["#########."; rows], "#Q=[](I)IIIIII", rules)
+}
+
+// stand the I piece up in the well and drop it after `elapsed` microseconds
+pub fn tetris(game: &mut Game, elapsed: u64) {
+    game.press(Action::RotateClockwise, elapsed);
+    for _ in 0..4 {
+        game.press(Action::MoveRight, elapsed);
+        game.release(Action::MoveRight, elapsed);
+    }
+    game.press(Action::HardDrop, elapsed);
+    game.release(Action::HardDrop, elapsed);
+    game.update(elapsed);
+}
+
+// squares of the active piece in a ten wide game, with y counting up from the floor like a fumen field
+pub fn squares(game: &Game) -> Vec<(isize, isize)> {
+    let rows = game.visible_board().len() as isize / 10;
+    let mut squares: Vec<_> = game.active_piece_indexes().iter()
+        .map(|idx| (*idx as isize % 10, rows - 1 - *idx as isize / 10))
+        .collect();
+    squares.sort();
+    squares
+}
+
+// where the piece would be at x, y in the given rotation, sorted like `squares`
+pub fn cells(ttype: PieceType, rotation: usize, x: isize, y: isize) -> Vec<(isize, isize)> {
+    let mut cells = Operation { ttype, rotation, x, y }.cells().to_vec();
+    cells.sort();
+    cells
+}
+
+// mash keys at random for a while, holding some of them down across frames
+pub fn play(game: &mut Game, rng: &mut Rng, frames: usize) {
+    for _ in 0..frames {
+        let elapsed = 8_000 + rng.below(20_000) as u64;
+
+        for _ in 0..rng.below(3) {
+            let action = ACTIONS[rng.below(ACTIONS.len())];
+            let at = rng.below(elapsed as usize) as u64;
+
+            if rng.below(2) == 0 {
+                game.press(action, at);
+            } else {
+                game.release(action, at);
+            }
+        }
+
+        game.update(elapsed);
+    }
+}
+
+pub fn assert_same(a: &Game, b: &Game) {
+    assert_eq!(a.visible_board(), b.visible_board());
+    assert_eq!(a.active_piece_indexes(), b.active_piece_indexes());
+    assert_eq!(a.ground_hint_indexes(), b.ground_hint_indexes());
+    assert_eq!(a.queue(), b.queue());
+    assert_eq!(a.held(), b.held());
+    assert_eq!(a.score, b.score);
+    assert_eq!(a.level, b.level);
+    assert_eq!(a.lines(), b.lines());
+    assert_eq!(a.lock_remaining(), b.lock_remaining());
+    assert_eq!(a.is_lost(), b.is_lost());
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use common::hard_drop;
+use tetris_rs::fumen::{self, Field, Operation, Page};
+use tetris_rs::{Color, Game, Mode, PieceType, Rules};
+
+// four rows of garbage six wide at the bottom
+const WELL: &str = "v115@9gF8DeF8DeF8DeF8NeAgH";
+
+// an I standing up against the left wall, then an O, an S and a Z in their other rotations
+// next to it, the way the editor writes them down
+const ROTATED: &str = "v115@vhDZEJjpBvrBcsB";
+
+#[test]
+fn fields_are_read_and_written_like_the_editor() {
+    let pages = fumen::decode(WELL).unwrap();
+    assert_eq!(pages.len(), 1);
+
+    let field = &pages[0].field;
+    for y in 0..4 {
+        for x in 0..10 {
+            let expected = if x < 6 { Color::Gray } else { Color::None };
+            assert_eq!(field.get(x, y), expected);
+        }
+    }
+    assert_eq!(field.get(0, 4), Color::None);
+
+    assert_eq!(fumen::encode(&pages), WELL);
+    assert_eq!(fumen::encode(&[Page::new(Field::new())]), "v115@vhAAgH");
+    assert!(fumen::decode("v115@vhAAg").is_err());
+    assert!(fumen::decode("v110@vhAAgH").is_err());
+}
+
+#[test]
+fn pieces_in_every_rotation_are_read_like_the_editor() {
+    let pages = fumen::decode(ROTATED).unwrap();
+    let cells = |page: &Page| {
+        let mut cells = page.operation.unwrap().cells().to_vec();
+        cells.sort();
+        cells
+    };
+
+    assert_eq!(cells(&pages[0]), vec![(0, 0), (0, 1), (0, 2), (0, 3)]);
+    assert_eq!(cells(&pages[1]), vec![(1, 0), (1, 1), (2, 0), (2, 1)]);
+    assert_eq!(cells(&pages[2]), vec![(4, 1), (4, 2), (5, 0), (5, 1)]);
+    assert_eq!(cells(&pages[3]), vec![(6, 0), (6, 1), (7, 1), (7, 2)]);
+
+    let field = pages[3].next_field();
+    let rows = [
+        "I.........",
+        "I...S..Z..",
+        "IOO.SSZZ..",
+        "IOO..SZ...",
+    ];
+    for (y, row) in rows.iter().rev().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            let expected = match c {
+                'I' => Color::Cyan,
+                'O' => Color::Yellow,
+                'S' => Color::Green,
+                'Z' => Color::Red,
+                _ => Color::None,
+            };
+            assert_eq!(field.get(x, y as isize), expected);
+        }
+    }
+
+    assert_eq!(fumen::encode(&pages), ROTATED);
+}
+
+#[test]
+fn positions_load_with_their_piece_and_queue() {
+    let mut page = fumen::decode(WELL).unwrap().remove(0);
+    page.operation = Some(Operation { ttype: PieceType::T, rotation: 0, x: 7, y: 0 });
+    page.comment = "#Q=[I](T)OSZJL".to_string();
+    let data = fumen::encode(&[page]);
+
+    let mut game = Game::from_fumen(&data, 0, Rules::default(), 1).unwrap();
+    assert_eq!(game.visible_board()[19 * 10], Color::Gray);
+    assert_eq!(game.held(), Some(PieceType::I));
+    assert_eq!(game.active_piece_type(), PieceType::T);
+    assert_eq!(game.queue(), &[PieceType::O, PieceType::S, PieceType::Z]);
+
+    let mut indexes = game.active_piece_indexes().to_vec();
+    indexes.sort();
+    assert_eq!(indexes, vec![18 * 10 + 7, 19 * 10 + 6, 19 * 10 + 7, 19 * 10 + 8]);
+
+    // the rest of the queue comes before the randomizer
+    hard_drop(&mut game, 1000);
+    hard_drop(&mut game, 1000);
+    assert_eq!(game.queue(), &[PieceType::Z, PieceType::J, PieceType::L]);
+
+    // a replay would start from an empty board
+    assert!(game.replay().is_none());
+    game.restart_with_seed(1);
+    assert!(game.replay().is_none());
+}
+
+#[test]
+fn gray_rows_are_the_garbage_to_dig() {
+    let rules = Rules { mode: Mode::Dig, ..Rules::default() };
+    let mut game = Game::from_fumen(WELL, 0, rules, 1).unwrap();
+    assert_eq!(game.garbage_remaining(), 4);
+    hard_drop(&mut game, 1000);
+    assert!(!game.is_finished());
+
+    assert!(Game::from_fumen("v115@vhAAgH", 0, rules, 1).is_err());
+    assert_eq!(Game::from_fumen("v115@vhAAgH", 0, Rules::default(), 1).unwrap().garbage_remaining(), 0);
+}
+
+#[test]
+fn placements_export_as_pages() {
+    let rules = Rules { undo_limit: 10, ..Rules::default() };
+    let mut game = Game::with_rules(10, 20, rules, 5).unwrap();
+    let first = game.active_piece_type();
+    hard_drop(&mut game, 1000);
+    hard_drop(&mut game, 1000);
+
+    let data = game.to_fumen().unwrap();
+    let pages = fumen::decode(&data).unwrap();
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[0].operation.map(|o| o.ttype), Some(first));
+    assert!(pages[0].lock);
+    assert!(!pages[2].lock);
+
+    // every page picks up where the previous one left off
+    let restored = Game::from_fumen(&data, 2, rules, 5).unwrap();
+    assert_eq!(restored.visible_board(), game.visible_board());
+    assert_eq!(restored.active_piece_type(), game.active_piece_type());
+    assert_eq!(restored.active_piece_indexes(), game.active_piece_indexes());
+    assert_eq!(restored.queue(), game.queue());
+}
+
+#[test]
+fn placements_export_without_undo() {
+    let mut game = Game::with_rules(10, 20, Rules::default(), 5).unwrap();
+    let first = game.active_piece_type();
+    hard_drop(&mut game, 1000);
+    let second = game.active_piece_type();
+    hard_drop(&mut game, 1000);
+
+    let pages = fumen::decode(&game.to_fumen().unwrap()).unwrap();
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[0].operation.map(|o| o.ttype), Some(first));
+    assert_eq!(pages[1].operation.map(|o| o.ttype), Some(second));
+    assert!(pages[0].lock && pages[1].lock);
+    assert_eq!(pages[0].field, Field::new());
+    assert_ne!(pages[1].field, Field::new());
+
+    // undone pieces are left out, and replaced once another one locks
+    let rules = Rules { undo_limit: 10, ..Rules::default() };
+    let mut game = Game::with_rules(10, 20, rules, 5).unwrap();
+    hard_drop(&mut game, 1000);
+    hard_drop(&mut game, 1000);
+    assert!(game.undo());
+    assert_eq!(fumen::decode(&game.to_fumen().unwrap()).unwrap().len(), 2);
+    assert!(game.redo());
+    assert_eq!(fumen::decode(&game.to_fumen().unwrap()).unwrap().len(), 3);
+
+    assert!(game.undo());
+    assert!(game.undo());
+    hard_drop(&mut game, 1000);
+    assert_eq!(fumen::decode(&game.to_fumen().unwrap()).unwrap().len(), 2);
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{placed, squares};
+use tetris_rs::fumen::Operation;
+use tetris_rs::input::AutoShift;
+use tetris_rs::{Action, Game, PieceType, Rules};
+
+// a T up in the air with its left square in column 3
+fn floating(rules: Rules) -> Game {
+    let piece = Operation { ttype: PieceType::T, rotation: 0, x: 4, y: 10 };
+    placed(&[], piece, rules)
+}
+
+fn column(game: &Game) -> isize {
+    squares(game)[0].0
+}
+
+fn row(game: &Game) -> isize {
+    squares(game)[0].1
+}
+
+fn shifty() -> Rules {
+    Rules { das_ms: 100, arr_ms: 20, ..Rules::default() }
+}
+
+#[test]
+fn auto_shift_charges_das_then_repeats_every_arr() {
+    let ms = Duration::from_millis;
+    let mut shift = AutoShift::new();
+    assert_eq!(shift.charge(ms(500), ms(100), ms(20)), Some(0));
+
+    shift.press(1);
+    assert_eq!(shift.charge(ms(99), ms(100), ms(20)), Some(0));
+    assert_eq!(shift.charge(ms(1), ms(100), ms(20)), Some(1));
+    assert_eq!(shift.charge(ms(19), ms(100), ms(20)), Some(0));
+    assert_eq!(shift.charge(ms(61), ms(100), ms(20)), Some(4));
+    assert_eq!(shift.charge(ms(1), ms(100), ms(0)), None);
+}
+
+#[test]
+fn holding_a_direction_shifts_after_das_and_every_arr() {
+    let mut game = floating(shifty());
+    assert_eq!(column(&game), 3);
+
+    game.press(Action::MoveRight, 0);
+    game.update(0);
+    assert_eq!(column(&game), 4);
+
+    game.update(99_000);
+    assert_eq!(column(&game), 4);
+    game.update(1_000);
+    assert_eq!(column(&game), 5);
+
+    game.update(20_000);
+    assert_eq!(column(&game), 6);
+    game.update(20_000);
+    assert_eq!(column(&game), 7);
+
+    // and then it stays against the wall
+    game.update(100_000);
+    assert_eq!(column(&game), 7);
+}
+
+#[test]
+fn an_arr_of_zero_goes_straight_to_the_wall() {
+    let mut game = floating(Rules { arr_ms: 0, ..shifty() });
+    game.press(Action::MoveLeft, 0);
+    game.update(99_000);
+    assert_eq!(column(&game), 2);
+
+    game.update(1_000);
+    assert_eq!(column(&game), 0);
+}
+
+#[test]
+fn letting_go_of_one_direction_charges_the_other_from_scratch() {
+    let mut game = floating(shifty());
+    game.press(Action::MoveRight, 0);
+    game.press(Action::MoveLeft, 0);
+    game.release(Action::MoveLeft, 50_000);
+    game.update(50_000);
+    assert_eq!(column(&game), 3);
+
+    // right has been held all along, but it only counts from when left was let go
+    game.update(99_000);
+    assert_eq!(column(&game), 3);
+    game.update(1_000);
+    assert_eq!(column(&game), 4);
+
+    // letting go of the key that isn't charging changes nothing
+    game.press(Action::MoveLeft, 0);
+    game.release(Action::MoveRight, 0);
+    game.update(99_000);
+    assert_eq!(column(&game), 3);
+    game.update(1_000);
+    assert_eq!(column(&game), 2);
+}
+
+#[test]
+fn soft_drop_multiplies_gravity() {
+    // a row a second at level 1, twenty times that while soft dropping
+    let mut game = floating(Rules::default());
+    game.press(Action::SoftDrop, 0);
+    game.update(100_000);
+    assert_eq!(row(&game), 8);
+    assert_eq!(game.score, 2);
+
+    game.release(Action::SoftDrop, 0);
+    game.update(900_000);
+    assert_eq!(row(&game), 8);
+
+    let mut game = floating(Rules { soft_drop_factor: 0, ..Rules::default() });
+    game.press(Action::SoftDrop, 0);
+    game.update(0);
+    assert_eq!(row(&game), 0);
+}
+
+// a T against the left wall, rotating it first lets it move left, moving first doesn't
+fn against_the_wall(inputs: &[(Action, u64)]) -> isize {
+    let piece = Operation { ttype: PieceType::T, rotation: 0, x: 1, y: 10 };
+    let mut game = placed(&[], piece, Rules::default());
+    for (action, at) in inputs {
+        game.press(*action, *at);
+    }
+    game.update(16_000);
+    column(&game)
+}
+
+#[test]
+fn inputs_in_a_frame_apply_in_order() {
+    let rotate_first = against_the_wall(&[(Action::RotateClockwise, 0), (Action::MoveLeft, 0)]);
+    let move_first = against_the_wall(&[(Action::MoveLeft, 0), (Action::RotateClockwise, 0)]);
+    assert_eq!(rotate_first, 0);
+    assert_eq!(move_first, 1);
+
+    // it's the time they were pressed that counts, not when they were sent
+    let move_earlier = against_the_wall(&[(Action::RotateClockwise, 5_000), (Action::MoveLeft, 2_000)]);
+    assert_eq!(move_earlier, 1);
+}
+
+#[test]
+fn inputs_land_before_or_after_gravity_inside_a_frame() {
+    // the T drops onto the floor 12ms into the frame, after that the block stops it moving right
+    let shift_at = |at: u64| {
+        let stack = [
+            "..........",
+            "......#...",
+        ];
+        let piece = Operation { ttype: PieceType::T, rotation: 0, x: 4, y: 1 };
+        let mut game = placed(&stack, piece, Rules::default());
+        game.update(988_000);
+
+        game.press(Action::MoveRight, at);
+        game.update(20_000);
+        squares(&game)[0]
+    };
+
+    assert_eq!(shift_at(10_000), (4, 1));
+    assert_eq!(shift_at(15_000), (3, 0));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{placed, squares, tetris, well};
+use tetris_rs::fumen::Operation;
+use tetris_rs::{Error, Game, GravityCurve, LevelGoal, LineClear, PieceType, Rules, TSpin, TWENTY_G};
+
+const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+#[test]
+fn gravity_curves_speed_up_with_the_level() {
+    use GravityCurve::*;
+
+    assert_eq!(Guideline.fall_rate(1), Duration::from_secs(1));
+    assert!(Guideline.fall_rate(2) < Guideline.fall_rate(1));
+    assert_eq!(Guideline.fall_rate(20), Guideline.fall_rate(30));
+
+    // level 1 is the NES level 0
+    assert_eq!(Nes.fall_rate(1), Duration::from_nanos(48 * 16_639_267));
+    assert_eq!(Nes.fall_rate(30), Duration::from_nanos(16_639_267));
+    assert_eq!(Nes.fall_rate(100), Nes.fall_rate(30));
+
+    assert_eq!(Twenty.fall_rate(1), TWENTY_G);
+    assert_eq!(Master.fall_rate(0), FRAME * 64);
+    assert_eq!(Master.fall_rate(200), FRAME * 64);
+    assert_eq!(Master.fall_rate(251), FRAME);
+    assert_eq!(Master.fall_rate(500), TWENTY_G);
+}
+
+#[test]
+fn level_goals_count_lines_or_points() {
+    let tetris = LineClear { tspin: TSpin::None, lines: 4, combo: 0, back_to_back: false, perfect_clear: false };
+    let tsd = LineClear { tspin: TSpin::Full, lines: 2, back_to_back: true, ..tetris };
+
+    assert_eq!(LevelGoal::Fixed.goal(7), 10);
+    assert_eq!(LevelGoal::Fixed.awarded(&tsd), 2);
+    assert_eq!(LevelGoal::Variable.goal(7), 35);
+    assert_eq!(LevelGoal::Variable.awarded(&tetris), 8);
+    assert_eq!(LevelGoal::Variable.awarded(&tsd), 18);
+}
+
+#[test]
+fn clears_level_up_the_game() {
+    let mut game = well(12, Rules::default());
+    tetris(&mut game, 0);
+    tetris(&mut game, 0);
+    assert_eq!(game.level, 1);
+    assert_eq!(game.goal_remaining(), 2);
+
+    tetris(&mut game, 0);
+    assert_eq!(game.level, 2);
+    assert_eq!(game.goal_remaining(), 8);
+
+    // a tetris is worth 8 of the 5 lines of level 1, the rest carries over
+    let mut game = well(4, Rules { level_goal: LevelGoal::Variable, ..Rules::default() });
+    tetris(&mut game, 0);
+    assert_eq!(game.level, 2);
+    assert_eq!(game.goal_remaining(), 7);
+}
+
+#[test]
+fn pieces_fall_as_many_rows_as_the_frame_took() {
+    let piece = Operation { ttype: PieceType::T, rotation: 0, x: 4, y: 15 };
+    let mut game = placed(&[], piece, Rules::default());
+    let start = squares(&game);
+    let fallen = |game: &Game| start[0].1 - squares(game)[0].1;
+
+    game.update(3_500_000);
+    assert_eq!(fallen(&game), 3);
+
+    // the half row left over counts towards the next one
+    game.update(500_000);
+    assert_eq!(fallen(&game), 4);
+}
+
+#[test]
+fn start_levels_are_checked() {
+    let rules = Rules { start_level: 1000, ..Rules::default() };
+    assert_eq!(Game::with_rules(10, 20, rules, 1).err(), Some(Error::InvalidStartLevel(1000)));
+
+    let rules = Rules { start_level: usize::MAX, ..Rules::default() };
+    assert!(Game::with_rules(10, 20, rules, 1).is_err());
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{placed, tap};
+use tetris_rs::fumen::Operation;
+use tetris_rs::{Action, Game, LockPolicy, PieceType, Rules};
+
+// a T resting on the floor with the default half second lock delay
+fn grounded(lock_policy: LockPolicy) -> Game {
+    let piece = Operation { ttype: PieceType::T, rotation: 0, x: 4, y: 0 };
+    placed(&[], piece, Rules { lock_policy, ..Rules::default() })
+}
+
+fn is_locked(game: &Game) -> bool {
+    *game.playfield().rows().last().unwrap() != 0
+}
+
+// slide back and forth at the start of a frame and then wait
+fn shuffle(game: &mut Game, frame: usize, elapsed: u64) {
+    let shift = if frame.is_multiple_of(2) { Action::MoveLeft } else { Action::MoveRight };
+    tap(game, shift);
+    game.update(elapsed);
+}
+
+#[test]
+fn lock_delay_counts_down_on_the_ground() {
+    let mut game = grounded(LockPolicy::MoveReset);
+    assert_eq!(game.lock_remaining(), 500_000);
+
+    game.update(200_000);
+    assert_eq!(game.lock_remaining(), 300_000);
+    game.update(299_000);
+    assert!(!is_locked(&game));
+
+    game.update(1_000);
+    assert!(is_locked(&game));
+    assert_eq!(game.lock_remaining(), 500_000);
+}
+
+#[test]
+fn infinite_lock_delay_never_runs_out_while_moving() {
+    let mut game = grounded(LockPolicy::Infinite);
+    game.update(400_000);
+
+    for frame in 0..100 {
+        shuffle(&mut game, frame, 400_000);
+    }
+    assert!(!is_locked(&game));
+}
+
+#[test]
+fn move_reset_runs_out_after_fifteen_resets() {
+    let mut game = grounded(LockPolicy::MoveReset);
+    game.update(400_000);
+
+    for frame in 0..15 {
+        shuffle(&mut game, frame, 400_000);
+    }
+    assert!(!is_locked(&game));
+    assert_eq!(game.lock_remaining(), 100_000);
+
+    // the sixteenth move doesn't buy any more time
+    shuffle(&mut game, 15, 100_000);
+    assert!(is_locked(&game));
+}
+
+#[test]
+fn step_reset_only_waits_out_the_delay() {
+    let mut game = grounded(LockPolicy::StepReset);
+    game.update(400_000);
+
+    shuffle(&mut game, 0, 99_000);
+    assert!(!is_locked(&game));
+    shuffle(&mut game, 1, 1_000);
+    assert!(is_locked(&game));
+}
+
+#[test]
+fn lock_delay_starts_when_the_piece_lands() {
+    // a row a second at level 1, the T lands a second into the update
+    let piece = Operation { ttype: PieceType::T, rotation: 0, x: 4, y: 1 };
+    let mut game = placed(&[], piece, Rules::default());
+    game.update(1_400_000);
+    assert!(!is_locked(&game));
+    assert_eq!(game.lock_remaining(), 100_000);
+
+    game.update(100_000);
+    assert!(is_locked(&game));
+}
@@ -0,0 +1,246 @@
+mod common;
+
+use common::{hard_drop, tetris, well};
+use tetris_rs::{Action, Error, Game, GarbageHoles, Grade, Mode, PieceType, Rules, ScoringRuleKind, TopOut};
+
+#[test]
+fn sprint_finishes_at_the_line_target_with_splits() {
+    let rules = Rules { mode: Mode::Sprint, line_target: 12, ..Rules::default() };
+    let mut game = well(12, rules);
+
+    tetris(&mut game, 100_000);
+    tetris(&mut game, 100_000);
+    assert_eq!(game.lines(), 8);
+    assert_eq!(game.lines_remaining(), 4);
+    assert!(game.splits().is_empty());
+    assert!(!game.is_finished());
+
+    tetris(&mut game, 100_000);
+    assert!(game.is_finished());
+    assert!(!game.is_lost());
+    assert_eq!(game.lines_remaining(), 0);
+    assert_eq!(game.game_time(), 300_000);
+    assert_eq!(game.splits(), vec![300_000]);
+
+    // the clock stops with the game
+    game.update(100_000);
+    assert_eq!(game.game_time(), 300_000);
+}
+
+#[test]
+fn sprint_needs_lines_to_clear() {
+    let rules = Rules { mode: Mode::Sprint, line_target: 0, ..Rules::default() };
+    assert_eq!(Game::with_rules(10, 20, rules, 1).err(), Some(Error::InvalidGoal(Mode::Sprint)));
+
+    // marathon doesn't go for the line target at all
+    let rules = Rules { line_target: 0, ..Rules::default() };
+    assert!(Game::with_rules(10, 20, rules, 1).is_ok());
+}
+
+#[test]
+fn marathon_keeps_going_past_the_line_target() {
+    let rules = Rules { line_target: 4, ..Rules::default() };
+    let mut game = well(8, rules);
+
+    tetris(&mut game, 0);
+    tetris(&mut game, 0);
+    assert_eq!(game.lines(), 8);
+    assert!(!game.is_finished());
+}
+
+#[test]
+fn ultra_ends_when_time_runs_out() {
+    let rules = Rules { mode: Mode::Ultra, time_limit_ms: 1000, ..Rules::default() };
+    let mut game = well(8, rules);
+    assert_eq!(game.time_remaining(), 1_000_000);
+
+    tetris(&mut game, 400_000);
+    assert_eq!(game.time_remaining(), 600_000);
+    let score = game.score;
+
+    // the drop comes in after the time limit and doesn't count
+    game.update(500_000);
+    assert!(!game.is_time_up());
+    tetris(&mut game, 200_000);
+    assert!(game.is_time_up());
+    assert!(!game.is_lost());
+    assert_eq!(game.time_remaining(), 0);
+    assert_eq!(game.game_time(), 1_000_000);
+    assert_eq!(game.score, score);
+}
+
+fn dig(garbage_rows: usize, garbage_holes: GarbageHoles, seed: u64) -> Game {
+    let rules = Rules { mode: Mode::Dig, garbage_rows, garbage_holes, ..Rules::default() };
+    Game::with_rules(10, 20, rules, seed).unwrap()
+}
+
+// the column of the hole in every row of garbage from the bottom up
+fn holes(game: &Game) -> Vec<usize> {
+    let rows = game.playfield().rows();
+    rows.iter().rev().take(game.garbage_remaining())
+        .map(|row| (!row & 0x3ff).trailing_zeros() as usize)
+        .collect()
+}
+
+#[test]
+fn dig_starts_with_garbage_from_the_seed() {
+    let game = dig(8, GarbageHoles::Random, 3);
+    assert_eq!(game.garbage_remaining(), 8);
+
+    let rows = game.playfield().rows();
+    assert!(rows[rows.len() - 8..].iter().all(|row| row.count_ones() == 9));
+    assert_eq!(rows[rows.len() - 9], 0);
+    assert_eq!(holes(&game), holes(&dig(8, GarbageHoles::Random, 3)));
+
+    let messy = holes(&dig(8, GarbageHoles::Messy, 3));
+    assert!(messy.windows(2).all(|pair| pair[0] != pair[1]));
+}
+
+#[test]
+fn dig_needs_garbage_to_clear() {
+    let rules = Rules { mode: Mode::Dig, garbage_rows: 0, ..Rules::default() };
+    assert_eq!(Game::with_rules(10, 20, rules, 1).err(), Some(Error::InvalidGoal(Mode::Dig)));
+}
+
+#[test]
+fn dig_finishes_when_the_garbage_is_gone() {
+    // a seed where the garbage is one straight well
+    let mut game = (0..)
+        .map(|seed| dig(4, GarbageHoles::Clean, seed))
+        .find(|game| {
+            let holes = holes(game);
+            holes.iter().all(|hole| *hole == holes[0]) && game.active_piece_type() == PieceType::I
+        })
+        .unwrap();
+    let hole = holes(&game)[0] as isize;
+
+    // the I stands up in the sixth column
+    game.press(Action::RotateClockwise, 0);
+    let shift = if hole < 5 { Action::MoveLeft } else { Action::MoveRight };
+    for _ in 0..(hole - 5).abs() {
+        game.press(shift, 0);
+        game.release(shift, 0);
+    }
+    hard_drop(&mut game, 0);
+
+    assert_eq!(game.garbage_remaining(), 0);
+    assert!(game.is_finished());
+}
+
+#[test]
+fn survival_garbage_rises_faster_and_faster() {
+    let rules = Rules { mode: Mode::Survival, garbage_interval_ms: 1000, ..Rules::default() };
+    let mut game = Game::with_rules(10, 20, rules, 2).unwrap();
+    assert_eq!(game.next_garbage(), 1_000_000);
+
+    game.update(1_000_000);
+    assert_eq!(game.garbage_remaining(), 1);
+    assert_eq!(game.playfield().rows().last().unwrap().count_ones(), 9);
+
+    // the next row comes 5% sooner
+    assert_eq!(game.next_garbage(), 950_000);
+    game.update(900_000);
+    assert_eq!(game.garbage_remaining(), 1);
+    game.update(50_000);
+    assert_eq!(game.garbage_remaining(), 2);
+
+    // the ghost sits on the new garbage
+    let ghost = game.ground_hint_indexes().iter().max().unwrap() / 10;
+    assert_eq!(ghost, 17);
+
+    // with nobody playing the pieces and the garbage meet at the top soon enough
+    for _ in 0..120 {
+        game.update(1_000_000);
+    }
+    assert!(game.is_lost());
+    assert_ne!(game.top_out(), TopOut::None);
+}
+
+fn lowest_row(game: &Game) -> u32 {
+    game.active_piece_indexes().iter().max().unwrap() / 10
+}
+
+#[test]
+fn master_pieces_spawn_on_the_stack_at_twenty_g() {
+    let mut game = Game::with_rules(10, 20, Rules { start_level: 500, ..Rules::master() }, 1).unwrap();
+    assert_eq!(lowest_row(&game), 19);
+
+    game.press(Action::MoveLeft, 0);
+    game.update(0);
+    assert_eq!(lowest_row(&game), 19);
+
+    let slow = Game::with_rules(10, 20, Rules::master(), 1).unwrap();
+    assert!(lowest_row(&slow) < 5);
+}
+
+#[test]
+fn master_pieces_wait_out_the_entry_delay() {
+    let mut game = Game::with_rules(10, 20, Rules::master(), 1).unwrap();
+    hard_drop(&mut game, 0);
+    assert_eq!(game.entry_remaining(), 500_000);
+    assert!(game.active_piece_indexes().is_empty());
+    assert!(game.ground_hint_indexes().is_empty());
+
+    // holding while waiting doesn't do anything
+    let next = game.active_piece_type();
+    game.press(Action::Hold, 0);
+    game.update(499_000);
+    assert_eq!(game.active_piece_type(), next);
+    assert!(game.active_piece_indexes().is_empty());
+
+    game.update(1_000);
+    assert_eq!(game.entry_remaining(), 0);
+    assert!(!game.active_piece_indexes().is_empty());
+
+    // clearing lines waits a bit longer
+    let rules = Rules { are_ms: 100, line_clear_delay_ms: 200, ..Rules::default() };
+    let mut game = well(8, rules);
+    tetris(&mut game, 0);
+    assert_eq!(game.entry_remaining(), 300_000);
+}
+
+#[test]
+fn master_levels_stop_at_the_end_of_a_section() {
+    let mut game = Game::with_rules(10, 20, Rules::master(), 1).unwrap();
+    for _ in 0..3 {
+        hard_drop(&mut game, 0);
+        game.update(500_000);
+    }
+    assert_eq!(game.level, 3);
+
+    let rules = Rules { mode: Mode::Master, start_level: 99, ..Rules::default() };
+    let mut game = Game::with_rules(10, 20, rules, 1).unwrap();
+    hard_drop(&mut game, 0);
+    assert_eq!(game.level, 99);
+
+    // only a clear gets past the stop and ends the section
+    let rules = Rules { mode: Mode::Master, start_level: 98, ..Rules::default() };
+    let mut game = well(8, rules);
+    tetris(&mut game, 100_000);
+    assert_eq!(game.level, 103);
+    assert_eq!(game.section_times(), vec![100_000]);
+}
+
+#[test]
+fn master_is_graded_by_score_until_grand_master() {
+    assert_eq!(Grade::for_score(0), Grade::Nine);
+    assert_eq!(Grade::for_score(12000), Grade::One);
+    assert_eq!(Grade::for_score(125000), Grade::S9);
+    assert_eq!(Grade::Gm.name(), "GM");
+
+    let rules = Rules { mode: Mode::Master, start_level: 995, scoring_rule: ScoringRuleKind::Tgm, ..Rules::default() };
+    let mut game = well(8, rules);
+    tetris(&mut game, 100_000);
+    assert!(game.is_finished());
+    assert_eq!(game.level, 999);
+    assert!(game.score < 126000);
+    assert_eq!(game.grade(), Grade::for_score(game.score));
+
+    // starting at 995 skips the checkpoints, so even a GM score only gets S9
+    let mut game = well(8, rules);
+    game.score = 126000;
+    tetris(&mut game, 100_000);
+    assert_eq!(game.level, 999);
+    assert_eq!(game.grade(), Grade::for_score(game.score));
+    assert_eq!(game.grade(), Grade::S9);
+}
@@ -0,0 +1,105 @@
+use tetris_rs::{Bag, PieceType, Randomizer, RandomizerKind, Rng};
+
+fn deal(kind: RandomizerKind, seed: u64, count: usize) -> Vec<PieceType> {
+    let mut randomizer = kind.build();
+    let mut rng = Rng::new(seed);
+    (0..count).map(|_| randomizer.next(&mut rng)).collect()
+}
+
+fn counts(pieces: &[PieceType]) -> [usize; 7] {
+    let mut counts = [0; 7];
+    for piece in pieces {
+        counts[*piece as usize - 1] += 1;
+    }
+    counts
+}
+
+// every piece should come up close to a seventh of the time
+fn assert_even(kind: RandomizerKind) {
+    let pieces = deal(kind, 99, 70_000);
+    for count in counts(&pieces).iter() {
+        assert!((9_000..11_000).contains(count), "{:?} dealt {:?}", kind, counts(&pieces));
+    }
+}
+
+#[test]
+fn randomizers_are_seedable() {
+    use RandomizerKind::*;
+
+    for kind in [SevenBag, FourteenBag, SevenPlusOne, Random, Nes, Tgm, Tgm3].iter() {
+        assert_eq!(deal(*kind, 5, 200), deal(*kind, 5, 200));
+        assert_ne!(deal(*kind, 5, 200), deal(*kind, 6, 200));
+    }
+}
+
+#[test]
+fn randomizers_deal_every_piece_evenly() {
+    use RandomizerKind::*;
+
+    for kind in [SevenBag, FourteenBag, SevenPlusOne, Random, Nes, Tgm, Tgm3].iter() {
+        assert_even(*kind);
+    }
+}
+
+#[test]
+fn bags_hold_every_piece() {
+    for bag in deal(RandomizerKind::SevenBag, 1, 700).chunks(7) {
+        assert_eq!(counts(bag), [1; 7]);
+    }
+
+    for bag in deal(RandomizerKind::FourteenBag, 1, 1400).chunks(14) {
+        assert_eq!(counts(bag), [2; 7]);
+    }
+
+    for bag in deal(RandomizerKind::SevenPlusOne, 1, 800).chunks(8) {
+        assert!(counts(bag).iter().all(|count| *count >= 1));
+    }
+}
+
+#[test]
+fn empty_bags_still_deal() {
+    let mut bag = Bag::new(0, 0);
+    let mut rng = Rng::new(1);
+    let pieces: Vec<_> = (0..7_000).map(|_| bag.next(&mut rng)).collect();
+    assert!(counts(&pieces).iter().all(|count| *count > 800));
+}
+
+#[test]
+fn nes_rarely_repeats() {
+    let pieces = deal(RandomizerKind::Nes, 3, 70_000);
+    let repeats = pieces.windows(2).filter(|pair| pair[0] == pair[1]).count();
+
+    // a repeat needs two rolls in a row to land on it, about 1 in 28 against 1 in 7 for pure random
+    assert!(repeats < 70_000 / 20, "{} repeats", repeats);
+}
+
+#[test]
+fn tgm_avoids_recent_pieces() {
+    use PieceType::*;
+
+    for kind in [RandomizerKind::Tgm, RandomizerKind::Tgm3].iter() {
+        for seed in 0..100 {
+            assert!(![S, Z, O].contains(&deal(*kind, seed, 1)[0]));
+        }
+
+        let pieces = deal(*kind, 3, 70_000);
+        let repeats = pieces.windows(2).filter(|pair| pair[0] == pair[1]).count();
+        assert!(repeats < 70_000 / 50, "{:?} repeated {} times", kind, repeats);
+    }
+}
+
+#[test]
+fn tgm3_ends_droughts() {
+    let pieces = deal(RandomizerKind::Tgm3, 11, 70_000);
+
+    for piece in PieceType::ALL.iter() {
+        let mut drought = 0;
+        let mut longest = 0;
+        for dealt in pieces.iter() {
+            drought = if dealt == piece { 0 } else { drought + 1 };
+            longest = longest.max(drought);
+        }
+
+        assert!(longest < 40, "{:?} went {} pieces without showing up", piece, longest);
+    }
+}
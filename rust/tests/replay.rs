@@ -0,0 +1,85 @@
+mod common;
+
+use common::{assert_same, hard_drop, play};
+use tetris_rs::{Bag, Error, Game, Replay, ReplayPlayer, Rng, Rules};
+
+#[test]
+fn replays_reproduce_the_game() {
+    let rules = Rules { das_ms: 100, arr_ms: 0, ..Rules::default() };
+    let mut game = Game::with_rules(10, 20, rules, 2024).unwrap();
+    play(&mut game, &mut Rng::new(99), 3000);
+
+    let replay = Replay::from_json(&game.replay_json().unwrap()).unwrap();
+    assert_eq!(replay, game.replay().unwrap());
+
+    // mashing tops out well before the last frame and the replay ends there
+    assert!(game.is_lost());
+    assert!(replay.frames.len() < 3000);
+
+    assert_same(&game, &replay.play().unwrap());
+}
+
+#[test]
+fn replays_play_back_frame_by_frame() {
+    let mut game = Game::new_with_seed(10, 20, 8).unwrap();
+    play(&mut game, &mut Rng::new(99), 500);
+
+    let mut player = ReplayPlayer::new(game.replay().unwrap()).unwrap();
+    let mut steps = 0;
+    while player.step() {
+        steps += 1;
+    }
+
+    assert_eq!(steps, game.replay().unwrap().frames.len());
+    assert!(player.is_finished());
+    assert_same(&game, player.game());
+}
+
+#[test]
+fn replays_from_other_versions_are_rejected() {
+    let game = Game::new_with_seed(10, 20, 8).unwrap();
+    let json = game.replay_json().unwrap().replace("\"version\":1", "\"version\":2");
+
+    assert!(matches!(Replay::from_json(&json), Err(Error::InvalidReplay(_))));
+    assert!(matches!(Replay::from_json("{}"), Err(Error::InvalidReplay(_))));
+}
+
+#[test]
+fn replays_stop_with_the_game() {
+    let mut game = Game::new_with_seed(10, 20, 8).unwrap();
+    let mut frames = 0;
+    while !game.is_lost() {
+        hard_drop(&mut game, 16_000);
+        frames += 1;
+    }
+    assert_eq!(game.replay().unwrap().frames.len(), frames);
+
+    // the frontend keeps ticking on the game over screen
+    for _ in 0..10 {
+        hard_drop(&mut game, 16_000);
+    }
+    assert_eq!(game.replay().unwrap().frames.len(), frames);
+    assert!(game.replay().unwrap().play().unwrap().is_lost());
+}
+
+#[test]
+fn games_a_replay_cant_reproduce_have_none() {
+    // a bag of two sets deals differently from the rules' seven bag
+    let mut game = Game::with_randomizer(10, 20, Rules::default(), 8, Box::new(Bag::new(2, 0))).unwrap();
+    play(&mut game, &mut Rng::new(99), 100);
+    assert!(game.replay().is_none());
+    game.restart_with_seed(8);
+    assert!(game.replay().is_none());
+
+    let mut game = Game::new_with_seed(10, 20, 8).unwrap();
+    hard_drop(&mut game, 16_000);
+    game.add_garbage_row(3);
+    hard_drop(&mut game, 16_000);
+    assert!(game.replay().is_none());
+    assert!(game.replay_json().is_none());
+
+    // a new game after it is recorded again
+    game.restart_with_seed(8);
+    play(&mut game, &mut Rng::new(99), 100);
+    assert_same(&game, &game.replay().unwrap().play().unwrap());
+}
@@ -0,0 +1,195 @@
+mod common;
+
+use common::{placed, squares, tap};
+use tetris_rs::fumen::Operation;
+use tetris_rs::{has_square, Action, Ars, Block, Game, Kick, Nes, PieceType, RotationSystem, RotationSystemKind, Rules, Srs};
+
+// the wiki's tables with y pointing up, for the transitions 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L
+const WIKI_JLSTZ: [[Kick; 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+];
+
+const WIKI_I: [[Kick; 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+];
+
+const TRANSITIONS: [(usize, usize); 8] = [(0, 1), (1, 0), (1, 2), (2, 1), (2, 3), (3, 2), (3, 0), (0, 3)];
+
+// the board's y axis points down
+fn flipped(kicks: &[Kick]) -> Vec<Kick> {
+    kicks.iter().map(|(x, y)| (*x, -*y)).collect()
+}
+
+#[test]
+fn srs_kicks_match_the_wiki() {
+    for (i, (from, to)) in TRANSITIONS.iter().enumerate() {
+        for ttype in [PieceType::T, PieceType::S, PieceType::Z, PieceType::J, PieceType::L] {
+            assert_eq!(Srs.kicks(ttype, *from, *to).to_vec(), flipped(&WIKI_JLSTZ[i]));
+        }
+        assert_eq!(Srs.kicks(PieceType::I, *from, *to).to_vec(), flipped(&WIKI_I[i]));
+    }
+}
+
+#[test]
+fn rotating_back_tries_the_opposite_kicks() {
+    for (from, to) in TRANSITIONS {
+        for ttype in [PieceType::I, PieceType::T] {
+            let there = Srs.kicks(ttype, from, to);
+            let back = Srs.kicks(ttype, to, from);
+            assert!(there.iter().zip(back).all(|(a, b)| (a.0, a.1) == (-b.0, -b.1)));
+        }
+    }
+}
+
+#[test]
+fn o_pieces_and_half_turns_dont_kick() {
+    for (from, to) in TRANSITIONS {
+        assert_eq!(Srs.kicks(PieceType::O, from, to), &[(0, 0)]);
+    }
+    assert_eq!(Srs.kicks(PieceType::T, 0, 2), &[(0, 0)]);
+}
+
+// squares of a block as x, y in its 4x4 box
+fn block_squares(block: &Block) -> Vec<(usize, usize)> {
+    (0..16).map(|i| (i % 4, i / 4)).filter(|(x, y)| has_square(block, *x, *y)).collect()
+}
+
+#[test]
+fn ars_pieces_sit_at_the_bottom_of_their_box_and_kick_sideways() {
+    // a T pointing down has its flat side at the top of the box, pointing up it's one row lower
+    assert_eq!(block_squares(Ars.block(PieceType::T, 0)), vec![(0, 1), (1, 1), (2, 1), (1, 2)]);
+    assert_eq!(block_squares(Ars.block(PieceType::T, 2)), vec![(1, 1), (0, 2), (1, 2), (2, 2)]);
+
+    assert_eq!(Ars.kicks(PieceType::T, 0, 1), &[(0, 0), (1, 0), (-1, 0)]);
+    assert_eq!(Ars.kicks(PieceType::I, 0, 1), &[(0, 0)]);
+    assert_eq!(Ars.kicks(PieceType::O, 0, 1), &[(0, 0)]);
+}
+
+#[test]
+fn ars_center_column_stops_kicks() {
+    // the first blocked square the T runs into turning to L is in its middle column
+    let center = |x: usize, y: usize| (x, y) == (1, 0) || (x, y) == (0, 1);
+    assert!(!Ars.can_kick(PieceType::T, 3, &center));
+
+    // turning to R it runs into the left column first, so it can kick
+    let left = |x: usize, y: usize| (x, y) == (0, 1) || (x, y) == (1, 2);
+    assert!(Ars.can_kick(PieceType::T, 1, &left));
+
+    // only J, L and T follow the rule
+    assert!(Ars.can_kick(PieceType::S, 1, &center));
+}
+
+#[test]
+fn nes_never_kicks() {
+    for (from, to) in TRANSITIONS {
+        for ttype in [PieceType::I, PieceType::T, PieceType::L] {
+            assert_eq!(Nes.kicks(ttype, from, to), &[(0, 0)]);
+        }
+    }
+}
+
+const EMPTY: [&str; 0] = [];
+
+fn rotate(game: &mut Game, action: Action) {
+    tap(game, action);
+    game.update(0);
+}
+
+fn rules(rotation_system: RotationSystemKind) -> Rules {
+    Rules { rotation_system, ..Rules::default() }
+}
+
+#[test]
+fn srs_kicks_off_the_wall() {
+    // a T pointing right against the left wall can't flatten out in place
+    let piece = Operation { ttype: PieceType::T, rotation: 1, x: 0, y: 5 };
+    let mut game = placed(&EMPTY, piece, Rules::default());
+    rotate(&mut game, Action::RotateCounterClockwise);
+    assert_eq!(squares(&game), vec![(0, 5), (1, 5), (1, 6), (2, 5)]);
+
+    // an upright I against the left wall goes two columns out
+    let piece = Operation { ttype: PieceType::I, rotation: 1, x: 0, y: 5 };
+    let mut game = placed(&EMPTY, piece, Rules::default());
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), vec![(0, 4), (1, 4), (2, 4), (3, 4)]);
+}
+
+#[test]
+fn srs_kicks_up_off_the_floor() {
+    // the third kick for a T is one left and one up
+    let piece = Operation { ttype: PieceType::T, rotation: 0, x: 4, y: 0 };
+    let mut game = placed(&EMPTY, piece, Rules::default());
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), vec![(3, 0), (3, 1), (3, 2), (4, 1)]);
+
+    // a flat I on the floor only stands up with the last kick, two up and one right
+    let piece = Operation { ttype: PieceType::I, rotation: 0, x: 4, y: 0 };
+    let mut game = placed(&EMPTY, piece, Rules::default());
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), vec![(6, 0), (6, 1), (6, 2), (6, 3)]);
+}
+
+#[test]
+fn srs_kicks_off_the_stack() {
+    let stack = [
+        "..#.......",
+        "..#.......",
+    ];
+    let piece = Operation { ttype: PieceType::T, rotation: 1, x: 3, y: 1 };
+    let mut game = placed(&stack, piece, Rules::default());
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), vec![(3, 1), (4, 0), (4, 1), (5, 1)]);
+}
+
+#[test]
+fn ars_kicks_one_column_unless_the_center_is_blocked() {
+    let piece = Operation { ttype: PieceType::T, rotation: 1, x: 0, y: 5 };
+    let mut game = placed(&EMPTY, piece, rules(RotationSystemKind::Ars));
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), vec![(0, 5), (1, 4), (1, 5), (2, 5)]);
+
+    // a square above the middle of an upside down T keeps it from standing up,
+    // even though a kick to the right would fit
+    let stack = [
+        "....#.....",
+        "..........",
+        "..........",
+        "..........",
+        "..........",
+        "..........",
+    ];
+    let piece = Operation { ttype: PieceType::T, rotation: 2, x: 4, y: 4 };
+    let mut game = placed(&stack, piece, rules(RotationSystemKind::Ars));
+    let before = squares(&game);
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), before);
+}
+
+#[test]
+fn nes_turns_in_place_or_not_at_all() {
+    let piece = Operation { ttype: PieceType::T, rotation: 1, x: 0, y: 5 };
+    let mut game = placed(&EMPTY, piece, rules(RotationSystemKind::Nes));
+    let before = squares(&game);
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), before);
+
+    // out in the open it turns in place
+    let piece = Operation { ttype: PieceType::T, rotation: 1, x: 4, y: 5 };
+    let mut game = placed(&EMPTY, piece, rules(RotationSystemKind::Nes));
+    rotate(&mut game, Action::RotateClockwise);
+    assert_eq!(squares(&game), vec![(3, 5), (4, 4), (4, 5), (5, 5)]);
+}
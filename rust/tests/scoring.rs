@@ -0,0 +1,94 @@
+mod common;
+
+use common::{hard_drop, tetris, well};
+use tetris_rs::{GuidelineScoring, LineClear, NesScoring, Rules, ScoringRule, TSpin, TgmScoring};
+
+fn clear(tspin: TSpin, lines: usize) -> LineClear {
+    LineClear { tspin, lines, combo: 0, back_to_back: false, perfect_clear: false }
+}
+
+#[test]
+fn guideline_scores_clears_and_spins_by_level() {
+    let rule = GuidelineScoring;
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 0), 1), 0);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 1), 1), 100);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 4), 1), 800);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 4), 3), 2400);
+    assert_eq!(rule.line_clear(&clear(TSpin::Mini, 0), 1), 100);
+    assert_eq!(rule.line_clear(&clear(TSpin::Mini, 1), 1), 200);
+    assert_eq!(rule.line_clear(&clear(TSpin::Full, 0), 1), 400);
+    assert_eq!(rule.line_clear(&clear(TSpin::Full, 2), 1), 1200);
+    assert_eq!(rule.line_clear(&clear(TSpin::Full, 3), 2), 3200);
+
+    assert_eq!(rule.soft_drop(5), 5);
+    assert_eq!(rule.hard_drop(5), 10);
+}
+
+#[test]
+fn guideline_adds_back_to_back_combos_and_perfect_clears() {
+    let rule = GuidelineScoring;
+    let b2b = LineClear { back_to_back: true, ..clear(TSpin::None, 4) };
+    assert_eq!(rule.line_clear(&b2b, 1), 1200);
+    assert_eq!(rule.line_clear(&LineClear { back_to_back: true, ..clear(TSpin::Full, 2) }, 1), 1800);
+
+    let combo = LineClear { combo: 3, ..clear(TSpin::None, 1) };
+    assert_eq!(rule.line_clear(&combo, 2), 500);
+
+    let perfect = LineClear { perfect_clear: true, ..clear(TSpin::None, 1) };
+    assert_eq!(rule.line_clear(&perfect, 1), 900);
+    assert_eq!(rule.line_clear(&LineClear { perfect_clear: true, ..clear(TSpin::None, 4) }, 1), 2800);
+
+    // a back to back tetris perfect clear gets the bigger bonus on top of the tetris
+    let b2b_perfect = LineClear { perfect_clear: true, ..b2b };
+    assert_eq!(rule.line_clear(&b2b_perfect, 1), 1200 + 3200);
+}
+
+#[test]
+fn nes_only_counts_lines() {
+    let rule = NesScoring;
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 1), 1), 40);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 2), 1), 100);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 3), 1), 300);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 4), 3), 3600);
+    assert_eq!(rule.line_clear(&clear(TSpin::Full, 1), 1), 40);
+    assert_eq!(rule.line_clear(&LineClear { back_to_back: true, combo: 2, ..clear(TSpin::None, 4) }, 1), 1200);
+    assert_eq!(rule.hard_drop(10), 0);
+}
+
+#[test]
+fn tgm_scales_with_the_level_combo_and_bravo() {
+    let rule = TgmScoring;
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 0), 100), 0);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 1), 0), 1);
+    assert_eq!(rule.line_clear(&clear(TSpin::None, 4), 100), 104);
+    assert_eq!(rule.line_clear(&LineClear { combo: 1, ..clear(TSpin::None, 4) }, 100), 208);
+    assert_eq!(rule.line_clear(&LineClear { perfect_clear: true, ..clear(TSpin::None, 4) }, 100), 416);
+}
+
+#[test]
+fn combos_and_back_to_back_carry_across_locks() {
+    let mut game = well(8, Rules::default());
+
+    tetris(&mut game, 0);
+    let first = game.score;
+    assert_eq!(game.last_clear(), clear(TSpin::None, 4));
+
+    // both drops fall just as far, so the difference is all in the clear
+    tetris(&mut game, 0);
+    let second = LineClear { combo: 1, back_to_back: true, perfect_clear: true, ..clear(TSpin::None, 4) };
+    assert_eq!(game.last_clear(), second);
+    assert_eq!(game.score - first, first - 800 + 1200 + 50 + 3200);
+}
+
+#[test]
+fn a_lock_without_a_clear_breaks_the_combo_but_not_back_to_back() {
+    let mut game = well(12, Rules::default());
+    tetris(&mut game, 0);
+
+    // the flat I lands on the stack away from the well
+    hard_drop(&mut game, 0);
+    assert_eq!(game.last_clear(), clear(TSpin::None, 0));
+
+    tetris(&mut game, 0);
+    assert_eq!(game.last_clear(), LineClear { back_to_back: true, ..clear(TSpin::None, 4) });
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::{assert_same, play};
+use tetris_rs::{Error, Game, RandomizerKind, Rng, Rules};
+
+// a restored game has to carry on exactly like the one it was saved from
+fn check_restore(rules: Rules, restore: impl Fn(&Game) -> Game) {
+    let mut rng = Rng::new(5);
+    let mut game = Game::with_rules(10, 20, rules, 77).unwrap();
+    play(&mut game, &mut rng, 400);
+
+    let mut restored = restore(&game);
+    assert_same(&game, &restored);
+
+    play(&mut game, &mut rng.clone(), 400);
+    play(&mut restored, &mut rng, 400);
+    assert_same(&game, &restored);
+}
+
+#[test]
+fn binary_snapshots_restore_the_game() {
+    for randomizer in [RandomizerKind::SevenBag, RandomizerKind::Nes, RandomizerKind::Tgm3] {
+        let rules = Rules { randomizer, ..Rules::default() };
+        check_restore(rules, |game| Game::load_state(&game.save_state()).unwrap());
+    }
+}
+
+#[test]
+fn json_snapshots_restore_the_game() {
+    check_restore(Rules::default(), |game| Game::load_state_json(&game.save_state_json()).unwrap());
+}
+
+#[test]
+fn broken_snapshots_are_rejected() {
+    let game = Game::new_with_seed(10, 20, 1).unwrap();
+
+    let mut bytes = game.save_state();
+    bytes[0] = 0;
+    assert!(matches!(Game::load_state(&bytes), Err(Error::InvalidSnapshot(_))));
+    assert!(matches!(Game::load_state(&[1, 0]), Err(Error::InvalidSnapshot(_))));
+
+    let json = game.save_state_json().replacen("\"version\":", "\"version\":100", 1);
+    assert!(matches!(Game::load_state_json(&json), Err(Error::InvalidSnapshot(_))));
+}
+
+// a snapshot with one value changed, the way a corrupt or hand edited save might look
+fn edited(game: &Game, edit: impl Fn(&mut serde_json::Value)) -> String {
+    let mut snapshot: serde_json::Value = serde_json::from_str(&game.save_state_json()).unwrap();
+    edit(&mut snapshot);
+    snapshot.to_string()
+}
+
+#[test]
+fn corrupt_snapshots_are_rejected_instead_of_panicking() {
+    let game = Game::new_with_seed(10, 20, 1).unwrap();
+    assert!(Game::load_state_json(&edited(&game, |_| {})).is_ok());
+
+    let states = edited(&game, |snapshot| snapshot["active_piece"]["piece"]["state"] = 9.into());
+    assert!(matches!(Game::load_state_json(&states), Err(Error::InvalidSnapshot(_))));
+
+    let rows = edited(&game, |snapshot| snapshot["board"]["rows"][30] = (1u64 << 12).into());
+    assert!(matches!(Game::load_state_json(&rows), Err(Error::InvalidSnapshot(_))));
+
+    let bag = edited(&game, |snapshot| snapshot["generator"]["Bag"] = serde_json::json!(["T", "T"]));
+    assert!(matches!(Game::load_state_json(&bag), Err(Error::InvalidSnapshot(_))));
+
+    let rules = Rules { randomizer: RandomizerKind::Tgm3, ..Rules::default() };
+    let game = Game::with_rules(10, 20, rules, 1).unwrap();
+    assert!(Game::load_state_json(&edited(&game, |_| {})).is_ok());
+    for field in ["pool", "drought"] {
+        let empty = edited(&game, |snapshot| snapshot["generator"]["Pool"][field] = serde_json::json!([]));
+        assert!(matches!(Game::load_state_json(&empty), Err(Error::InvalidSnapshot(_))));
+    }
+    let drought = edited(&game, |snapshot| {
+        snapshot["generator"]["Pool"]["drought"] = serde_json::json!(["I", "I", "I", "I", "I", "I", "I"]);
+    });
+    assert!(matches!(Game::load_state_json(&drought), Err(Error::InvalidSnapshot(_))));
+}
+
+#[test]
+fn saves_without_the_replay_stay_the_same_size() {
+    let mut game = Game::new_with_seed(10, 20, 3).unwrap();
+    let before = game.save_state_json_without_replay().len();
+    for _ in 0..1000 {
+        game.update(16_000);
+    }
+    assert!(game.save_state_json().len() > before + 1000);
+    assert!(game.save_state_json_without_replay().len() < before + 100);
+
+    // the game carries on, but there's no telling what came before to replay it
+    let mut restored = Game::load_state_json(&game.save_state_json_without_replay()).unwrap();
+    assert_same(&game, &restored);
+    restored.update(16_000);
+    assert!(restored.replay().is_none());
+    assert!(Game::load_state_json(&game.save_state_json()).unwrap().replay().is_some());
+
+    // until it starts over
+    restored.restart_game();
+    restored.update(16_000);
+    assert_eq!(restored.replay().unwrap().frames.len(), 1);
+}
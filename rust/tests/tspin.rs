@@ -0,0 +1,73 @@
+mod common;
+
+use common::{hard_drop, placed, tap};
+use tetris_rs::fumen::Operation;
+use tetris_rs::tspin::detect;
+use tetris_rs::{Action, PieceType, RotationSystem, Rules, Srs, TSpin};
+
+// a T in the given state of its box with the corners around its center at 1, 1 taken
+fn spin(state: usize, kick: usize, corners: &[(isize, isize)]) -> TSpin {
+    let block = Srs.block(PieceType::T, state);
+    detect(block, kick, &|x, y| corners.contains(&(x, y)))
+}
+
+#[test]
+fn three_corners_make_a_spin() {
+    // pointing up the front corners are the ones above the center
+    assert_eq!(spin(0, 0, &[(0, 0), (2, 0), (0, 2)]), TSpin::Full);
+    assert_eq!(spin(0, 0, &[(0, 0), (0, 2), (2, 2)]), TSpin::Mini);
+    assert_eq!(spin(0, 0, &[(0, 0), (2, 0)]), TSpin::None);
+    assert_eq!(spin(0, 0, &[(0, 0), (2, 0), (0, 2), (2, 2)]), TSpin::Full);
+}
+
+#[test]
+fn the_front_turns_with_the_t() {
+    // pointing right the front corners are on the right
+    assert_eq!(spin(1, 0, &[(2, 0), (2, 2), (0, 2)]), TSpin::Full);
+    assert_eq!(spin(1, 0, &[(0, 0), (0, 2), (2, 2)]), TSpin::Mini);
+
+    // and pointing down they're below it
+    assert_eq!(spin(2, 0, &[(0, 2), (2, 2), (0, 0)]), TSpin::Full);
+    assert_eq!(spin(2, 0, &[(0, 0), (2, 0), (2, 2)]), TSpin::Mini);
+}
+
+#[test]
+fn the_triple_kick_upgrades_a_mini() {
+    assert_eq!(spin(0, 4, &[(0, 0), (0, 2), (2, 2)]), TSpin::Full);
+    assert_eq!(spin(0, 3, &[(0, 0), (0, 2), (2, 2)]), TSpin::Mini);
+}
+
+#[test]
+fn a_t_rotated_into_the_slot_scores_a_tsd() {
+    let stack = [
+        "...#......",
+        "###...####",
+        "####.#####",
+    ];
+    let piece = Operation { ttype: PieceType::T, rotation: 1, x: 4, y: 1 };
+    let mut game = placed(&stack, piece, Rules::default());
+    tap(&mut game, Action::RotateClockwise);
+    hard_drop(&mut game, 0);
+
+    let clear = game.last_clear();
+    assert_eq!(clear.tspin, TSpin::Full);
+    assert_eq!(clear.lines, 2);
+    assert_eq!(game.score, 1200);
+}
+
+#[test]
+fn a_t_that_moved_after_rotating_doesnt_spin() {
+    // the T drops into a spot with three corners taken after it was turned
+    let stack = [
+        "#.........",
+        "#.........",
+        "#.#.......",
+    ];
+    let piece = Operation { ttype: PieceType::T, rotation: 0, x: 1, y: 6 };
+    let mut game = placed(&stack, piece, Rules::default());
+    tap(&mut game, Action::RotateClockwise);
+    hard_drop(&mut game, 0);
+
+    assert_eq!(game.playfield().rows().last().unwrap().count_ones(), 3);
+    assert_eq!(game.last_clear().tspin, TSpin::None);
+}
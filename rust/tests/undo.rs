@@ -0,0 +1,116 @@
+mod common;
+
+use tetris_rs::{Action, Color, Game, PieceType, Rules};
+
+#[derive(Debug, PartialEq)]
+struct Position {
+    board: Vec<Color>,
+    active: PieceType,
+    queue: Vec<PieceType>,
+    score: usize,
+}
+
+fn position(game: &Game) -> Position {
+    Position {
+        board: game.visible_board().to_vec(),
+        active: game.active_piece_type(),
+        queue: game.queue().to_vec(),
+        score: game.score,
+    }
+}
+
+fn hard_drop(game: &mut Game, shift: Action) {
+    common::tap(game, shift);
+    common::hard_drop(game, 1000);
+}
+
+fn practice(undo_limit: usize) -> Game {
+    let rules = Rules { undo_limit, ..Rules::default() };
+    Game::with_rules(10, 20, rules, 11).unwrap()
+}
+
+#[test]
+fn undo_is_off_by_default() {
+    let mut game = Game::new_with_seed(10, 20, 11).unwrap();
+    hard_drop(&mut game, Action::MoveLeft);
+
+    assert!(!game.undo());
+    assert!(!game.redo());
+}
+
+#[test]
+fn undo_and_redo_move_through_placements() {
+    let mut game = practice(10);
+    let mut positions = vec![position(&game)];
+    for _ in 0..3 {
+        hard_drop(&mut game, Action::MoveLeft);
+        positions.push(position(&game));
+    }
+
+    for expected in positions[..3].iter().rev() {
+        assert!(game.undo());
+        assert_eq!(&position(&game), expected);
+    }
+    assert!(!game.undo());
+
+    assert!(game.redo());
+    assert_eq!(position(&game), positions[1]);
+}
+
+#[test]
+fn placing_after_undo_deals_the_same_pieces() {
+    let mut game = practice(10);
+    hard_drop(&mut game, Action::MoveLeft);
+    hard_drop(&mut game, Action::MoveLeft);
+    let dealt = position(&game);
+
+    assert!(game.undo());
+    hard_drop(&mut game, Action::MoveRight);
+
+    // the board is different but the pieces coming up aren't, and the old placement is gone
+    let placed = position(&game);
+    assert_ne!(placed.board, dealt.board);
+    assert_eq!(placed.active, dealt.active);
+    assert_eq!(placed.queue, dealt.queue);
+    assert!(!game.redo());
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut game = practice(2);
+    for _ in 0..5 {
+        hard_drop(&mut game, Action::MoveLeft);
+    }
+
+    assert!(game.undo());
+    assert!(game.undo());
+    assert!(!game.undo());
+}
+
+#[test]
+fn undoing_drops_the_replay() {
+    let mut game = practice(5);
+    hard_drop(&mut game, Action::MoveLeft);
+    assert!(!game.redo());
+    assert!(game.replay().is_some());
+
+    hard_drop(&mut game, Action::MoveRight);
+    assert!(game.undo());
+    assert!(game.redo());
+    assert!(game.replay().is_none());
+}
+
+#[test]
+fn loaded_games_start_their_history_over() {
+    let mut game = practice(5);
+    hard_drop(&mut game, Action::MoveLeft);
+    hard_drop(&mut game, Action::MoveRight);
+
+    let mut loaded = Game::load_state(&game.save_state()).unwrap();
+    assert_eq!(position(&loaded), position(&game));
+    assert!(!loaded.undo());
+
+    hard_drop(&mut loaded, Action::MoveLeft);
+    assert!(loaded.undo());
+    assert_eq!(position(&loaded), position(&game));
+}
@@ -38,6 +38,7 @@ export class Application {
         await this.renderer.setup();
         this.renderer.renderEmptyState();
         this.state.setupControls();
+        window.addEventListener("beforeunload", () => this.state.save());
     }
 
     static async start(canvas: HTMLCanvasElement): Promise<Application> {
@@ -22,6 +22,7 @@ const COLOR_GREEN = 0xb0cc13;
 const COLOR_RED = 0xff1149;
 const COLOR_BLUE = 0x3c9ee3;
 const COLOR_ORANGE = 0xff6536;
+const COLOR_GRAY = 0x8c8c8c;
 
 enum Color {
   None = 0,
@@ -31,7 +32,8 @@ enum Color {
   Green = 4,
   Red = 5,
   Blue = 6,
-  Orange = 7
+  Orange = 7,
+  Gray = 8
 }
 
 function toHex(color: Color): number {
@@ -52,6 +54,8 @@ function toHex(color: Color): number {
       return COLOR_BLUE;
     case Color.Orange:
       return COLOR_ORANGE;
+    case Color.Gray:
+      return COLOR_GRAY;
   }
 }
 
@@ -73,5 +77,6 @@ export {
   COLOR_GREEN,
   COLOR_RED,
   COLOR_BLUE,
-  COLOR_ORANGE
+  COLOR_ORANGE,
+  COLOR_GRAY
 };
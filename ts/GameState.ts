@@ -1,34 +1,57 @@
-import { Game } from "../pkg/index";
+import { Action, Game, TopOut } from "../pkg/index";
 import { memory } from "../pkg/index_bg";
 import { BOARD_HEIGHT, BOARD_WIDTH } from "./Constants";
 
+// where the game is saved when the tab is closed, so it can be picked up again
+const SAVE_KEY = "wasm-tetris-save";
+
 class GameState {
   private game: Game;
+  private readonly lockDelayMs: number;
+
+  // time of the last tick, inputs are stamped relative to it so the game can apply
+  // them at the right point of the next frame
+  private lastTick: number = performance.now();
 
   constructor() {
-    this.game = Game.new(BOARD_WIDTH, BOARD_HEIGHT);
+    this.game = GameState.load() ?? Game.new(BOARD_WIDTH, BOARD_HEIGHT);
+
+    const rules = this.game.rules();
+    this.lockDelayMs = rules.lock_delay_ms;
+    rules.free();
   }
 
   // get the indexes of active piece's location on game board
-  get activePieceIndexes(): Uint8Array {
+  get activePieceIndexes(): Uint32Array {
     const len = this.game.active_piece_coords_len();
     const ptr = this.game.active_piece_coords();
-    return new Uint8Array(memory.buffer, ptr, len);
+    return new Uint32Array(memory.buffer, ptr, len);
   }
 
   get activePieceColor(): number {
     return this.game.active_piece_color();
   }
 
+  // 0 while the active piece is in the air, rising to 1 as it is about to lock,
+  // with no lock delay at all it's always about to lock
+  get lockProgress(): number {
+    if (this.lockDelayMs === 0) {
+      return 1;
+    }
+
+    const remaining = Number(this.game.lock_remaining()) / 1000;
+    return 1 - remaining / this.lockDelayMs;
+  }
+
   get board(): Uint8Array {
     const ptr = this.game.board();
     return new Uint8Array(memory.buffer, ptr, BOARD_WIDTH * BOARD_HEIGHT);
   }
 
-  get groundHintIndexes(): Uint8Array {
+  get groundHintIndexes(): Uint32Array {
     const len = this.game.ground_hint_coords_len();
     const ptr = this.game.ground_hint_coords();
-    return new Uint8Array(memory.buffer, ptr, len);
+    return new Uint32Array(memory.buffer, ptr, len);
   }
 
   get nextPieces(): Uint8Array {
@@ -48,46 +71,104 @@ class GameState {
     return this.game.is_lost();
   }
 
+  // topped out, reached the goal of the mode or ran out of time
+  get isOver(): boolean {
+    return this.game.is_lost() || this.game.is_finished() || this.game.is_time_up();
+  }
+
+  // microseconds left in a timed mode
+  get timeRemaining(): number {
+    return Number(this.game.time_remaining());
+  }
+
+  get topOut(): TopOut {
+    return this.game.top_out();
+  }
+
+  // the game handles auto repeat itself, so only the first keydown of a held key is sent
   setupControls(): void {
     window.addEventListener("keydown", event => {
-      switch (event.key) {
-        case "ArrowLeft":
-          this.game.move_left();
-          break;
-        case "ArrowRight":
-          this.game.move_right();
-          break;
-        case "ArrowDown":
-          this.game.move_down();
-          break;
-        case " ":
-          this.game.drop();
-          break;
-        case "Z":
-        case "z":
-          this.game.rotate_counter_clockwise();
-          break;
-        case "X":
-        case "x":
-          this.game.rotate_clockwise();
-          break;
-        case "C":
-        case "c":
-          this.game.hold();
-          break;
-
-        default:
-          return;
+      const action = GameState.actionFor(event.key);
+      if (action === undefined) {
+        return;
+      }
+
+      if (!event.repeat) {
+        this.game.press(action, this.frameOffset(event));
       }
+      event.preventDefault();
+    });
 
+    window.addEventListener("keyup", event => {
+      const action = GameState.actionFor(event.key);
+      if (action === undefined) {
+        return;
+      }
+
+      this.game.release(action, this.frameOffset(event));
       event.preventDefault();
     });
   }
 
+  // microseconds between the last tick and the key event
+  private frameOffset(event: KeyboardEvent): bigint {
+    return BigInt(Math.max(0, Math.floor((event.timeStamp - this.lastTick) * 1000)));
+  }
+
+  private static actionFor(key: string): Action | undefined {
+    switch (key) {
+      case "ArrowLeft":
+        return Action.MoveLeft;
+      case "ArrowRight":
+        return Action.MoveRight;
+      case "ArrowDown":
+        return Action.SoftDrop;
+      case " ":
+        return Action.HardDrop;
+      case "Z":
+      case "z":
+        return Action.RotateCounterClockwise;
+      case "X":
+      case "x":
+        return Action.RotateClockwise;
+      case "C":
+      case "c":
+        return Action.Hold;
+      default:
+        return undefined;
+    }
+  }
+
   tick(elapsedMS: number): void {
+    this.lastTick = performance.now();
     this.game.update(BigInt(Math.floor(elapsedMS * 1000)));
   }
 
+  // the replay is left out so the save doesn't grow with the game, if the storage is full
+  // anyway there's nothing to do about it while the page is closing
+  save(): void {
+    try {
+      localStorage.setItem(SAVE_KEY, this.game.save_state_json_without_replay());
+    } catch {
+      localStorage.removeItem(SAVE_KEY);
+    }
+  }
+
+  // a save from an older version of the game can't be loaded, that one just starts over
+  private static load(): Game | undefined {
+    const saved = localStorage.getItem(SAVE_KEY);
+    if (saved === null) {
+      return undefined;
+    }
+
+    try {
+      return Game.load_state_json(saved);
+    } catch {
+      localStorage.removeItem(SAVE_KEY);
+      return undefined;
+    }
+  }
+
   restart(): void {
     this.game.restart_game();
   }
@@ -237,7 +237,7 @@ export class Renderer {
         this.renderHeldPiece(game);
         this.renderScore(game);
 
-        if (game.isLost && !this.restartButton.visible) {
+        if (game.isOver && !this.restartButton.visible) {
             this.restartButton.visible = true;
         }
     }
@@ -251,6 +251,9 @@ export class Renderer {
         const activePieceIndexes = game.activePieceIndexes;
         const [gridX, gridY] = Renderer.gridXY;
 
+        // fade the piece out while it's resting on the ground to show it's about to lock
+        const alpha = 1 - 0.5 * game.lockProgress;
+
         for (let i = 0; i < activePieceIndexes.length; i++) {
             let pieceX = activePieceIndexes[i] % BOARD_WIDTH;
             let pieceY = Math.floor(activePieceIndexes[i] / BOARD_WIDTH);
@@ -259,6 +262,7 @@ export class Renderer {
             const sprite = new Sprite(this.textures[`square_${color}.png`]);
             sprite.x = gridX + pieceX * TETRIMINO_WIDTH_PX;
             sprite.y = gridY + pieceY * TETRIMINO_WIDTH_PX;
+            sprite.alpha = alpha;
 
             this.activePieceSprites.push(sprite);
             this.pixi.stage.addChild(sprite);
@@ -276,7 +280,7 @@ export class Renderer {
             for (let x = 0; x < BOARD_WIDTH; x++) {
                 let i = y * BOARD_WIDTH + x;
                 if (board[i] !== 0) {
-                    const sprite = new Sprite(this.textures[`square_${board[i]}.png`]);
+                    const sprite = Renderer.squareSprite(this.textures, board[i]);
                     sprite.x = gridX + x * TETRIMINO_WIDTH_PX;
                     sprite.y = gridY + y * TETRIMINO_WIDTH_PX;
 
@@ -287,6 +291,19 @@ export class Renderer {
         }
     }
 
+    // the tileset only has the piece colors, garbage is drawn as a tinted plain square
+    private static squareSprite(textures: utils.Dict<Texture>, color: Color): Sprite {
+        if (color === Color.Gray) {
+            const sprite = new Sprite(Texture.WHITE);
+            sprite.width = TETRIMINO_WIDTH_PX;
+            sprite.height = TETRIMINO_WIDTH_PX;
+            sprite.tint = toHex(color);
+            return sprite;
+        }
+
+        return new Sprite(textures[`square_${color}.png`]);
+    }
+
     private renderGroundHint(game: GameState): void {
         this.groundHintSquares.forEach(sprite =>
             this.pixi.stage.removeChild(sprite)
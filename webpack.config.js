@@ -49,7 +49,8 @@ module.exports = {
         new WasmPackPlugin({
             crateDirectory: crate,
             outDir: "../pkg",
-            outName: "index"
+            outName: "index",
+            extraArgs: "--features wasm"
         })
     ]
 };